@@ -1,16 +1,14 @@
-use fltk_decl::{DeclarativeApp, Widget};
+use fltk_decl::{DeclarativeApp, Error, Widget};
+use std::path::Path;
 
 // declare how you would like to deserialize
-fn load_fn(path: &'static str) -> Option<Widget> {
-    let s = std::fs::read_to_string(path).ok()?;
-    // We want to see the serde error on the command line while we're developing
-    serde_json5::from_str(&s).map_err(|e| eprintln!("{e}")).ok()
+fn load_fn(path: &Path) -> Result<Widget, Error> {
+    let s = std::fs::read_to_string(path)?;
+    serde_json5::from_str(&s).map_err(|e| Error::parse("json5", e))
 }
 
-fn main() {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     // use the filetype and extension that you require.
     // `run` a callback that runs at least once, or whenever the gui file changes.
-    DeclarativeApp::new(200, 300, "MyApp", "examples/gui.json", load_fn)
-        .run(|_| {})
-        .unwrap();
+    DeclarativeApp::new(200, 300, "MyApp", "examples/gui.json", load_fn)?.run(|_| {})
 }
@@ -0,0 +1,34 @@
+use fltk::{prelude::*, *};
+use fltk_decl::{DeclarativeApp, Error, Widget};
+use std::path::Path;
+
+#[derive(Clone, Copy)]
+struct State {
+    count: i32,
+}
+
+impl State {
+    pub fn increment(&mut self, val: i32) {
+        let mut result: frame::Frame = app::widget_from_id("result").unwrap();
+        self.count += val;
+        result.set_label(&self.count.to_string());
+    }
+}
+
+fn load_fn(path: &Path) -> Result<Widget, Error> {
+    let s = std::fs::read_to_string(path)?;
+    serde_json5::from_str(&s).map_err(|e| Error::parse("json5", e))
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    app::GlobalState::new(State { count: 0 });
+    // callbacks are attached by name, and survive hot-reloading of the gui file
+    DeclarativeApp::new(200, 300, "MyApp", "examples/callbacks.json", load_fn)?
+        .register("increment", |_| {
+            app::GlobalState::<State>::get().with(|s| s.increment(1));
+        })
+        .register("decrement", |_| {
+            app::GlobalState::<State>::get().with(|s| s.increment(-1));
+        })
+        .run(|_| {})
+}
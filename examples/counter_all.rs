@@ -1,8 +1,7 @@
 use fltk::{prelude::*, *};
-use fltk_decl::{DeclarativeApp, Widget};
-use std::path::PathBuf;
+use fltk_decl::DeclarativeApp;
 
-// use the extension you require!
+// use the extension you require, along with the feature of its format, ex. `--features xml` for gui.xml!
 const PATH: &str = "examples/gui.json";
 
 #[derive(Clone, Copy)]
@@ -34,22 +33,7 @@ fn run_cb(_win: &mut window::Window) {
     }
 }
 
-fn load_fn(p: &'static str) -> Option<Widget> {
-    let path = PathBuf::from(p);
-    let ext = path.extension().unwrap().to_str().unwrap();
-    let s = std::fs::read_to_string(p).unwrap();
-    match ext {
-        "xml" => serde_xml_rs::from_str(&s).ok(),
-        "toml" => toml::from_str(&s).ok(),
-        "yaml" => serde_yaml::from_str(&s).ok(),
-        "scm" => serde_lexpr::from_str(&s).ok(),
-        _ => serde_json5::from_str(&s).ok(),
-    }
-}
-
-fn main() {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     app::GlobalState::new(State { count: 0 });
-    DeclarativeApp::new(200, 300, "MyApp", PATH, load_fn)
-        .run(run_cb)
-        .unwrap();
+    DeclarativeApp::from_file(200, 300, "MyApp", PATH)?.run(run_cb)
 }
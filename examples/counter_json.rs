@@ -1,5 +1,6 @@
 use fltk::{prelude::*, *};
-use fltk_decl::{DeclarativeApp, Widget};
+use fltk_decl::{DeclarativeApp, Error, Widget};
+use std::path::Path;
 
 // use the extension you require!
 const PATH: &str = "examples/gui.json";
@@ -23,22 +24,20 @@ fn btn_cb(b: &mut button::Button) {
     state.with(move |s| s.increment(val));
 }
 
-fn load_fn(path: &'static str) -> Option<Widget> {
-    let s = std::fs::read_to_string(path).ok()?;
-    serde_json5::from_str(&s).map_err(|e| eprintln!("{e}")).ok()
+fn load_fn(path: &Path) -> Result<Widget, Error> {
+    let s = std::fs::read_to_string(path)?;
+    serde_json5::from_str(&s).map_err(|e| Error::parse("json5", e))
 }
 
-fn main() {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     app::GlobalState::new(State { count: 0 });
-    DeclarativeApp::new(200, 300, "MyApp", PATH, load_fn)
-        .run(|_win| {
-            app::set_scheme(app::Scheme::Oxy);
-            if let Some(mut btn) = app::widget_from_id::<button::Button>("inc") {
-                btn.set_callback(btn_cb);
-            }
-            if let Some(mut btn) = app::widget_from_id::<button::Button>("dec") {
-                btn.set_callback(btn_cb);
-            }
-        })
-        .unwrap();
+    DeclarativeApp::new(200, 300, "MyApp", PATH, load_fn)?.run(|_win| {
+        app::set_scheme(app::Scheme::Oxy);
+        if let Some(mut btn) = app::widget_from_id::<button::Button>("inc") {
+            btn.set_callback(btn_cb);
+        }
+        if let Some(mut btn) = app::widget_from_id::<button::Button>("dec") {
+            btn.set_callback(btn_cb);
+        }
+    })
 }
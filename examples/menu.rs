@@ -6,15 +6,16 @@ use fltk_decl::DeclarativeApp;
 fn main() {
     #[cfg(feature = "json5")]
     DeclarativeApp::new_json5(200, 300, "MyApp", "examples/menu.json")
-        .run(|_win| {
-            if let Some(mut choice) = app::widget_from_id::<menu::Choice>("choice") {
-                choice.add_choice("JAN|FEB|MAR|APR|MAY|JUN|JUL|AUG|SEP|OCT|NOV|DEC");
-                choice.set_callback(|c| {
-                    if let Some(mut label) = app::widget_from_id::<frame::Frame>("label") {
-                        label.set_label(&format!("{:?}", c.choice()));
-                    }
-                });
+        .unwrap()
+        // the months are declared as the choice's items in menu.json
+        .register("month", |_| {
+            if let (Some(choice), Some(mut label)) = (
+                app::widget_from_id::<menu::Choice>("choice"),
+                app::widget_from_id::<frame::Frame>("label"),
+            ) {
+                label.set_label(&format!("{:?}", choice.choice()));
             }
         })
+        .run(|_| {})
         .unwrap();
 }
@@ -0,0 +1,270 @@
+use std::fmt;
+
+/// Errors which can occur while loading a gui description
+#[derive(Debug)]
+pub enum Error {
+    /// The gui file couldn't be read
+    Io(std::io::Error),
+    /// The gui description couldn't be deserialized
+    Parse {
+        /// The description format, ex. "json"
+        format: &'static str,
+        /// The deserializer's message
+        message: String,
+        /// The line of the error, if the deserializer reports it
+        line: Option<usize>,
+        /// The column of the error, if the deserializer reports it
+        column: Option<usize>,
+    },
+    /// The gui file's format couldn't be detected
+    UnknownFormat(std::path::PathBuf),
+    /// The gui file's format requires a cargo feature which isn't enabled
+    MissingFeature {
+        /// The format's name
+        format: &'static str,
+        /// The required feature
+        feature: &'static str,
+    },
+    /// The root widget's type is unknown, so there's no gui to build
+    UnknownWidget(String),
+    /// A property with an invalid value
+    InvalidProperty {
+        /// The property name
+        property: String,
+        /// The offending value
+        value: String,
+    },
+    /// The gui description has problems, see [`Strictness::Strict`](crate::Strictness::Strict)
+    Invalid(Vec<crate::Diagnostic>),
+    /// An included file couldn't be loaded
+    Include {
+        /// The included file
+        path: std::path::PathBuf,
+        /// The error loading it
+        error: Box<Error>,
+    },
+    /// A file includes itself, directly or through other included files
+    IncludeCycle(std::path::PathBuf),
+}
+
+impl Error {
+    /// Construct a parse error without location information, useful for custom loaders
+    pub fn parse<E: fmt::Display>(format: &'static str, e: E) -> Self {
+        Error::Parse {
+            format,
+            message: e.to_string(),
+            line: None,
+            column: None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{e}"),
+            Error::Parse {
+                format,
+                message,
+                line,
+                column,
+            } => {
+                write!(f, "{format} error")?;
+                if let Some(line) = line {
+                    write!(f, " at line {line}")?;
+                    if let Some(column) = column {
+                        write!(f, ", column {column}")?;
+                    }
+                }
+                write!(f, ": {message}")
+            }
+            Error::UnknownFormat(path) => {
+                write!(f, "Couldn't detect the format of {}", path.display())
+            }
+            Error::MissingFeature { format, feature } => write!(
+                f,
+                "Loading {format} requires enabling the {feature} feature of fltk-decl"
+            ),
+            Error::UnknownWidget(w) => write!(f, "Unknown widget: {w}"),
+            Error::InvalidProperty { property, value } => {
+                write!(f, "Invalid value for {property}: {value}")
+            }
+            Error::Invalid(diags) => {
+                write!(f, "{} problem(s) found", diags.len())?;
+                for d in diags {
+                    write!(f, "\n{d}")?;
+                }
+                Ok(())
+            }
+            Error::Include { path, error } => write!(f, "{}: {error}", path.display()),
+            Error::IncludeCycle(path) => {
+                write!(f, "{} is included recursively", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Include { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Parse {
+            format: "json",
+            message: e.to_string(),
+            line: Some(e.line()),
+            column: Some(e.column()),
+        }
+    }
+}
+
+#[cfg(feature = "json5")]
+impl From<serde_json5::Error> for Error {
+    fn from(e: serde_json5::Error) -> Self {
+        let serde_json5::Error::Message { msg, location } = e;
+        Error::Parse {
+            format: "json5",
+            message: msg,
+            line: location.as_ref().map(|l| l.line),
+            column: location.as_ref().map(|l| l.column),
+        }
+    }
+}
+
+#[cfg(feature = "xml")]
+impl From<serde_xml_rs::Error> for Error {
+    fn from(e: serde_xml_rs::Error) -> Self {
+        Error::parse("xml", e)
+    }
+}
+
+#[cfg(feature = "xml")]
+impl From<xml::reader::Error> for Error {
+    fn from(e: xml::reader::Error) -> Self {
+        use xml::common::Position;
+        let pos = e.position();
+        Error::Parse {
+            format: "xml",
+            message: e.msg().to_string(),
+            line: Some(pos.row as usize + 1),
+            column: Some(pos.column as usize + 1),
+        }
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<serde_yaml::Error> for Error {
+    fn from(e: serde_yaml::Error) -> Self {
+        let location = e.location();
+        Error::Parse {
+            format: "yaml",
+            message: e.to_string(),
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+        }
+    }
+}
+
+/// Compute the line and column of a byte offset into `src`, for deserializers which only report spans
+#[cfg(any(feature = "toml", feature = "kdl"))]
+fn location(src: &str, offset: usize) -> (Option<usize>, Option<usize>) {
+    match src.get(..offset) {
+        Some(before) => {
+            let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+            (
+                Some(before.matches('\n').count() + 1),
+                Some(before[line_start..].chars().count() + 1),
+            )
+        }
+        None => (None, None),
+    }
+}
+
+#[cfg(feature = "toml")]
+impl Error {
+    pub(crate) fn from_toml(src: &str, e: toml::de::Error) -> Self {
+        let (line, column) = e
+            .span()
+            .map(|span| location(src, span.start))
+            .unwrap_or((None, None));
+        Error::Parse {
+            format: "toml",
+            message: e.message().to_string(),
+            line,
+            column,
+        }
+    }
+}
+
+#[cfg(feature = "sexp")]
+impl From<serde_lexpr::Error> for Error {
+    fn from(e: serde_lexpr::Error) -> Self {
+        let location = e.location();
+        Error::Parse {
+            format: "s-expression",
+            message: e.to_string(),
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+        }
+    }
+}
+
+#[cfg(feature = "ron")]
+impl From<ron::error::SpannedError> for Error {
+    fn from(e: ron::error::SpannedError) -> Self {
+        Error::Parse {
+            format: "ron",
+            message: e.code.to_string(),
+            line: Some(e.position.line),
+            column: Some(e.position.col),
+        }
+    }
+}
+
+#[cfg(feature = "kdl")]
+impl From<kdl::KdlError> for Error {
+    fn from(e: kdl::KdlError) -> Self {
+        let (line, column) = location(&e.input, e.span.offset());
+        Error::Parse {
+            format: "kdl",
+            message: e.to_string(),
+            line,
+            column,
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "toml", feature = "kdl")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_are_located() {
+        let src = "widget = \"Column\"\n\n[[children]]\nwidgét = 1";
+        assert_eq!(location(src, 0), (Some(1), Some(1)));
+        assert_eq!(location(src, 9), (Some(1), Some(10)));
+        // right after a newline
+        assert_eq!(location(src, 18), (Some(2), Some(1)));
+        assert_eq!(location(src, 19), (Some(3), Some(1)));
+        // columns count characters, not bytes
+        assert_eq!(location(src, src.rfind('=').unwrap()), (Some(4), Some(8)));
+        assert_eq!(location(src, src.len()), (Some(4), Some(11)));
+        // out of bounds, or in the middle of a character
+        assert_eq!(location(src, src.len() + 1), (None, None));
+        assert_eq!(location(src, src.find('é').unwrap() + 1), (None, None));
+    }
+}
@@ -0,0 +1,507 @@
+use crate::{validate::child_path, value::Value, Diagnostic, DiagnosticKind, Error};
+use std::cell::RefCell;
+
+/// The root sections which are expanded while loading
+const SECTIONS: &[&str] = &["vars", "constants", "components"];
+
+thread_local! {
+    /// The variables of the file including the one being loaded
+    static INHERITED: RefCell<Vec<(String, Value)>> = const { RefCell::new(vec![]) };
+}
+
+/// Run `f`, usually loading an included file, with the including file's variables visible
+pub(crate) fn with_vars<T>(vars: &[(String, Value)], f: impl FnOnce() -> T) -> T {
+    let previous = INHERITED.with(|v| v.replace(vars.to_vec()));
+    let res = f();
+    INHERITED.with(|v| *v.borrow_mut() = previous);
+    res
+}
+
+/// The variables of the including file, if any
+pub(crate) fn inherited() -> Vec<(String, Value)> {
+    INHERITED.with(|v| v.borrow().clone())
+}
+
+/// Whether a description has nothing to expand, and can be deserialized as is
+pub(crate) fn is_plain(root: &Value) -> bool {
+    SECTIONS.iter().all(|s| root.get(s).is_none()) && !has_reference(root)
+}
+
+/// Whether a value contains a string which may be a `$name` reference, or a `$$` escape
+fn has_reference(value: &Value) -> bool {
+    match value {
+        Value::String(s) => s.starts_with('$'),
+        Value::Seq(seq) => seq.iter().any(has_reference),
+        Value::Map(map) => map.iter().any(|(_, v)| has_reference(v)),
+        _ => false,
+    }
+}
+
+fn section(
+    root: &mut Value,
+    name: &str,
+    expected: &str,
+) -> Result<Option<Vec<(String, Value)>>, Error> {
+    match root.remove(name) {
+        Some(Value::Map(map)) => Ok(Some(map)),
+        Some(_) => Err(Error::InvalidProperty {
+            property: name.to_string(),
+            value: format!("expected a map of {expected}"),
+        }),
+        None => Ok(None),
+    }
+}
+
+/// A description whose variables are resolved and whose components are expanded
+#[derive(Debug)]
+pub(crate) struct Expanded {
+    pub(crate) root: Value,
+    /// The variables visible to the files it includes
+    pub(crate) vars: Vec<(String, Value)>,
+    /// The references to undeclared variables, kept as is, along with the path of their widget
+    pub(crate) unresolved: Vec<(String, String)>,
+}
+
+/// Resolve the `$name` references to the root's `vars` (or `constants`) section, or to those of
+/// the including file, then expand the instances of the components declared in its `components` section
+pub(crate) fn expand(mut root: Value) -> Result<Expanded, Error> {
+    let vars = section(&mut root, "vars", "values")?;
+    let constants = section(&mut root, "constants", "values")?;
+    let components = section(&mut root, "components", "widgets")?.unwrap_or_default();
+    // the file's own variables shadow the inherited ones
+    let vars: Vec<_> = vars
+        .into_iter()
+        .chain(constants)
+        .flatten()
+        .chain(inherited())
+        .collect();
+    let mut unresolved = vec![];
+    let root = resolve(root, &vars, "", &mut unresolved);
+    let mut expander = Expander {
+        components: &components,
+        vars: &vars,
+        stack: vec![],
+        diags: vec![],
+        unresolved,
+    };
+    let root = expander.widget(root, String::new());
+    if expander.diags.is_empty() {
+        let unresolved = expander.unresolved;
+        Ok(Expanded {
+            root,
+            vars,
+            unresolved,
+        })
+    } else {
+        Err(Error::Invalid(expander.diags))
+    }
+}
+
+/// Replace the strings referencing a variable, ex. `"$primary"`, by its value.
+/// References to undeclared variables are kept, and pushed to `unresolved` along with the widget's path
+fn resolve(
+    value: Value,
+    vars: &[(String, Value)],
+    path: &str,
+    unresolved: &mut Vec<(String, String)>,
+) -> Value {
+    match value {
+        Value::String(s) => match reference(&s) {
+            Some(Reference::Var(name)) => match vars.iter().find(|(k, _)| k == name) {
+                Some((_, v)) => v.clone(),
+                None => {
+                    unresolved.push((path.to_string(), name.to_string()));
+                    Value::String(s)
+                }
+            },
+            Some(Reference::Escaped(s)) => Value::String(s.to_string()),
+            None => Value::String(s),
+        },
+        Value::Seq(seq) => Value::Seq(
+            seq.into_iter()
+                .map(|v| resolve(v, vars, path, unresolved))
+                .collect(),
+        ),
+        Value::Map(map) => Value::Map(
+            map.into_iter()
+                .map(|(k, v)| {
+                    let v = match (k.as_str(), v) {
+                        ("children", Value::Seq(children)) => Value::Seq(
+                            children
+                                .into_iter()
+                                .enumerate()
+                                .map(|(i, c)| resolve(c, vars, &child_path(path, i), unresolved))
+                                .collect(),
+                        ),
+                        // only declared by the root
+                        ("windows", Value::Seq(windows)) => Value::Seq(
+                            windows
+                                .into_iter()
+                                .enumerate()
+                                .map(|(i, w)| {
+                                    resolve(w, vars, &format!("windows[{i}]"), unresolved)
+                                })
+                                .collect(),
+                        ),
+                        (_, v) => resolve(v, vars, path, unresolved),
+                    };
+                    (k, v)
+                })
+                .collect(),
+        ),
+        v => v,
+    }
+}
+
+enum Reference<'a> {
+    /// `$name`
+    Var(&'a str),
+    /// `$$name`, a literal `$name`
+    Escaped(&'a str),
+}
+
+fn reference(s: &str) -> Option<Reference<'_>> {
+    let name = s.strip_prefix('$')?;
+    if name.starts_with('$') {
+        return Some(Reference::Escaped(name));
+    }
+    let mut chars = name.chars();
+    let first = chars.next()?;
+    if (first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    {
+        Some(Reference::Var(name))
+    } else {
+        None
+    }
+}
+
+struct Expander<'a> {
+    components: &'a [(String, Value)],
+    vars: &'a [(String, Value)],
+    /// The components being expanded, to detect recursion
+    stack: Vec<&'a str>,
+    diags: Vec<Diagnostic>,
+    unresolved: Vec<(String, String)>,
+}
+
+impl<'a> Expander<'a> {
+    fn widget(&mut self, mut value: Value, path: String) -> Value {
+        let component = value
+            .get("widget")
+            .and_then(Value::as_str)
+            .and_then(|ty| self.components.iter().find(|(name, _)| name == ty));
+        if let Some((name, body)) = component {
+            if self.stack.contains(&name.as_str()) {
+                self.diags.push(Diagnostic {
+                    path,
+                    kind: DiagnosticKind::RecursiveComponent(name.clone()),
+                });
+                return value;
+            }
+            let props = match value.remove("props") {
+                Some(Value::Map(props)) => props,
+                _ => vec![],
+            };
+            // the body's references are resolved where it's instantiated
+            let body = resolve(body.clone(), self.vars, &path, &mut self.unresolved);
+            let mut expanded = self.substitute(body, name, &props, &path);
+            // properties set on the instance override those of the component's root
+            value.remove("widget");
+            if let Value::Map(overrides) = value {
+                for (k, v) in overrides {
+                    expanded.set(k, v);
+                }
+            }
+            self.stack.push(name);
+            let expanded = self.widget(expanded, path);
+            self.stack.pop();
+            return expanded;
+        }
+        if let Some(Value::Seq(children)) = value.remove("children") {
+            let children = children
+                .into_iter()
+                .enumerate()
+                .map(|(i, c)| self.widget(c, child_path(&path, i)))
+                .collect();
+            value.set("children".to_string(), Value::Seq(children));
+        }
+        // the other top-level windows, only declared by the root
+        if let Some(Value::Seq(windows)) = value.remove("windows") {
+            let windows = windows
+                .into_iter()
+                .enumerate()
+                .map(|(i, w)| self.widget(w, format!("windows[{i}]")))
+                .collect();
+            value.set("windows".to_string(), Value::Seq(windows));
+        }
+        value
+    }
+
+    /// Replace the `{{param}}` placeholders of a component's body by the instance's props
+    fn substitute(
+        &mut self,
+        value: Value,
+        component: &str,
+        props: &[(String, Value)],
+        path: &str,
+    ) -> Value {
+        match value {
+            Value::String(s) => {
+                // a placeholder making up the whole string keeps the prop's type, ex. a number
+                if let Some(param) = placeholder(&s) {
+                    return match self.prop(component, props, path, param) {
+                        Some(v) => v,
+                        None => Value::String(s),
+                    };
+                }
+                let mut out = String::new();
+                let mut rest = s.as_str();
+                while let Some((before, after)) = rest.split_once("{{") {
+                    let Some((param, after)) = after.split_once("}}") else {
+                        break;
+                    };
+                    out.push_str(before);
+                    match self
+                        .prop(component, props, path, param.trim())
+                        .and_then(|v| v.to_text())
+                    {
+                        Some(text) => out.push_str(&text),
+                        None => out.push_str(&rest[before.len()..rest.len() - after.len()]),
+                    }
+                    rest = after;
+                }
+                out.push_str(rest);
+                Value::String(out)
+            }
+            Value::Seq(seq) => Value::Seq(
+                seq.into_iter()
+                    .map(|v| self.substitute(v, component, props, path))
+                    .collect(),
+            ),
+            Value::Map(map) => Value::Map(
+                map.into_iter()
+                    .map(|(k, v)| (k, self.substitute(v, component, props, path)))
+                    .collect(),
+            ),
+            v => v,
+        }
+    }
+
+    fn prop(
+        &mut self,
+        component: &str,
+        props: &[(String, Value)],
+        path: &str,
+        param: &str,
+    ) -> Option<Value> {
+        let prop = props
+            .iter()
+            .find(|(k, _)| k == param)
+            .map(|(_, v)| v.clone());
+        if prop.is_none() {
+            self.diags.push(Diagnostic {
+                path: path.to_string(),
+                kind: DiagnosticKind::MissingParameter {
+                    component: component.to_string(),
+                    parameter: param.to_string(),
+                },
+            });
+        }
+        prop
+    }
+}
+
+/// The parameter name if the string is a single placeholder, ex. `{{label}}`
+fn placeholder(s: &str) -> Option<&str> {
+    let param = s.trim().strip_prefix("{{")?.strip_suffix("}}")?;
+    if param.contains("{{") || param.contains("}}") {
+        None
+    } else {
+        Some(param.trim())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(s: &str) -> Value {
+        serde_json5::from_str(s).unwrap()
+    }
+
+    fn expanded(s: &str) -> Value {
+        expand(value(s)).unwrap().root
+    }
+
+    fn diagnostics(s: &str) -> Vec<Diagnostic> {
+        match expand(value(s)) {
+            Err(Error::Invalid(diags)) => diags,
+            res => panic!("expected diagnostics, got {res:?}"),
+        }
+    }
+
+    fn child(v: &Value, i: usize) -> &Value {
+        match v.get("children") {
+            Some(Value::Seq(children)) => &children[i],
+            _ => panic!("no children in {v:?}"),
+        }
+    }
+
+    #[test]
+    fn placeholders_are_substituted() {
+        let root = expanded(
+            r#"{
+                components: {
+                    LabeledInput: {
+                        widget: "Row",
+                        children: [
+                            { widget: "Frame", label: "{{label}}:" },
+                            { widget: "Input", id: "{{id}}", tooltip: "{{label}} ({{id}})" },
+                        ],
+                    },
+                },
+                widget: "Column",
+                children: [{ widget: "LabeledInput", props: { label: "Name", id: "name" } }],
+            }"#,
+        );
+        let row = child(&root, 0);
+        assert_eq!(row.get("widget"), Some(&value(r#""Row""#)));
+        assert_eq!(row.get("props"), None);
+        assert_eq!(child(row, 0).get("label"), Some(&value(r#""Name:""#)));
+        assert_eq!(child(row, 1).get("id"), Some(&value(r#""name""#)));
+        assert_eq!(
+            child(row, 1).get("tooltip"),
+            Some(&value(r#""Name (name)""#))
+        );
+    }
+
+    #[test]
+    fn whole_placeholders_keep_the_prop_type() {
+        let root = expanded(
+            r#"{
+                components: { Wide: { widget: "Button", w: "{{width}}", label: "{{width}}px", hide: "{{ hidden }}" } },
+                widget: "Wide",
+                props: { width: 100, hidden: true },
+            }"#,
+        );
+        assert_eq!(root.get("w"), Some(&Value::Int(100)));
+        assert_eq!(root.get("label"), Some(&value(r#""100px""#)));
+        assert_eq!(root.get("hide"), Some(&Value::Bool(true)));
+    }
+
+    #[test]
+    fn missing_parameters_are_reported() {
+        let diags = diagnostics(
+            r#"{
+                components: { Titled: { widget: "Frame", label: "{{title}}" } },
+                widget: "Column",
+                children: [{ widget: "Frame" }, { widget: "Titled", props: { label: "Ignored" } }],
+            }"#,
+        );
+        assert_eq!(
+            diags,
+            [Diagnostic {
+                path: "children[1]".to_string(),
+                kind: DiagnosticKind::MissingParameter {
+                    component: "Titled".to_string(),
+                    parameter: "title".to_string(),
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn recursive_components_are_reported() {
+        let diags = diagnostics(
+            r#"{
+                components: {
+                    A: { widget: "Column", children: [{ widget: "B" }] },
+                    B: { widget: "Row", children: [{ widget: "A" }] },
+                },
+                widget: "A",
+            }"#,
+        );
+        assert_eq!(
+            diags,
+            [Diagnostic {
+                path: "children[0].children[0]".to_string(),
+                kind: DiagnosticKind::RecursiveComponent("A".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn components_can_use_other_components() {
+        let root = expanded(
+            r#"{
+                components: {
+                    Field: { widget: "Input", label: "{{name}}" },
+                    Form: { widget: "Column", children: [{ widget: "Field", props: { name: "{{first}}" } }] },
+                },
+                widget: "Form",
+                props: { first: "Email" },
+            }"#,
+        );
+        assert_eq!(root.get("widget"), Some(&value(r#""Column""#)));
+        let field = child(&root, 0);
+        assert_eq!(field.get("widget"), Some(&value(r#""Input""#)));
+        assert_eq!(field.get("label"), Some(&value(r#""Email""#)));
+    }
+
+    #[test]
+    fn instance_properties_override_the_component() {
+        let root = expanded(
+            r##"{
+                components: { Ok: { widget: "Button", label: "Ok", color: "#00ff00" } },
+                widget: "Ok",
+                color: "#ff0000",
+                id: "ok",
+            }"##,
+        );
+        assert_eq!(root.get("widget"), Some(&value(r#""Button""#)));
+        assert_eq!(root.get("label"), Some(&value(r#""Ok""#)));
+        assert_eq!(root.get("color"), Some(&value(r##""#ff0000""##)));
+        assert_eq!(root.get("id"), Some(&value(r#""ok""#)));
+    }
+
+    #[test]
+    fn vars_are_resolved() {
+        let root = expanded(
+            r#"{
+                vars: { size: 20, name: "Ok" },
+                widget: "Button",
+                labelsize: "$size",
+                label: "$name",
+                tooltip: "$$name",
+            }"#,
+        );
+        assert_eq!(root.get("labelsize"), Some(&Value::Int(20)));
+        assert_eq!(root.get("label"), Some(&value(r#""Ok""#)));
+        assert_eq!(root.get("tooltip"), Some(&value(r#""$name""#)));
+        // undeclared ones are kept, even without a vars section
+        let expanded = expand(value(
+            r#"{
+                components: { Total: { widget: "Frame", label: "$Total" } },
+                widget: "Column",
+                children: [{ widget: "Button", label: "$missing" }, { widget: "Total" }],
+            }"#,
+        ))
+        .unwrap();
+        assert_eq!(
+            child(&expanded.root, 0).get("label"),
+            Some(&value(r#""$missing""#))
+        );
+        assert_eq!(
+            child(&expanded.root, 1).get("label"),
+            Some(&value(r#""$Total""#))
+        );
+        assert_eq!(
+            expanded.unresolved,
+            [
+                ("children[0]".to_string(), "missing".to_string()),
+                ("children[1]".to_string(), "Total".to_string()),
+            ]
+        );
+    }
+}
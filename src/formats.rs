@@ -0,0 +1,742 @@
+use crate::{expand, value::Value, Error, Widget};
+use std::path::Path;
+
+/// The supported description formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// json, requires the json or json5 feature
+    Json,
+    /// json5, requires the json5 feature
+    Json5,
+    /// yaml, requires the yaml feature
+    Yaml,
+    /// xml, requires the xml feature
+    Xml,
+    /// toml, requires the toml feature
+    Toml,
+    /// s-expression, requires the sexp feature
+    Sexp,
+    /// ron, requires the ron feature
+    Ron,
+    /// kdl, requires the kdl feature
+    Kdl,
+}
+
+impl Format {
+    /// Get the format from a file extension
+    pub fn from_extension(ext: &str) -> Option<Format> {
+        match ext.to_ascii_lowercase().as_str() {
+            "json" => Some(Format::Json),
+            "json5" => Some(Format::Json5),
+            "yaml" | "yml" => Some(Format::Yaml),
+            "xml" => Some(Format::Xml),
+            "toml" => Some(Format::Toml),
+            "scm" | "sexp" | "lisp" => Some(Format::Sexp),
+            "ron" => Some(Format::Ron),
+            "kdl" => Some(Format::Kdl),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from the content of a description
+    pub fn sniff(s: &str) -> Option<Format> {
+        // skip comments, which are ambiguous between formats
+        let mut rest = s;
+        loop {
+            rest = rest.trim_start();
+            if rest.starts_with('#') || rest.starts_with("//") || rest.starts_with(';') {
+                rest = rest.split_once('\n').map(|(_, r)| r).unwrap_or("");
+            } else {
+                break;
+            }
+        }
+        if rest.is_empty() {
+            None
+        } else if rest.starts_with('<') {
+            Some(Format::Xml)
+        } else if let Some(inner) = rest.strip_prefix('(') {
+            // ron structs start with a field name, s-expressions with a nested list
+            let inner = inner.trim_start();
+            let word = inner
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(inner.len());
+            if word > 0 && inner[word..].trim_start().starts_with(':') {
+                Some(Format::Ron)
+            } else {
+                Some(Format::Sexp)
+            }
+        } else if rest.starts_with('{') {
+            // json5 is a superset of json
+            if cfg!(feature = "json5") {
+                Some(Format::Json5)
+            } else {
+                Some(Format::Json)
+            }
+        } else if rest.starts_with('[') {
+            Some(Format::Toml)
+        } else if rest.starts_with('-') {
+            Some(Format::Yaml)
+        } else {
+            let word = rest
+                .find(|c: char| c.is_whitespace() || "=:{(".contains(c))
+                .unwrap_or(rest.len());
+            match rest[word..].trim_start_matches([' ', '\t']).chars().next() {
+                Some('=') => Some(Format::Toml),
+                Some(':') => Some(Format::Yaml),
+                Some('(') => Some(Format::Ron),
+                // a kdl node name, followed by its entries or children
+                _ => Some(Format::Kdl),
+            }
+        }
+    }
+
+    /// The format's name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Json5 => "json5",
+            Format::Yaml => "yaml",
+            Format::Xml => "xml",
+            Format::Toml => "toml",
+            Format::Sexp => "s-expression",
+            Format::Ron => "ron",
+            Format::Kdl => "kdl",
+        }
+    }
+
+    /// The cargo feature required by the format
+    pub fn feature(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Json5 => "json5",
+            Format::Yaml => "yaml",
+            Format::Xml => "xml",
+            Format::Toml => "toml",
+            Format::Sexp => "sexp",
+            Format::Ron => "ron",
+            Format::Kdl => "kdl",
+        }
+    }
+
+    /// Deserialize a description in this format, resolving its variables and expanding its components
+    #[allow(unused_variables)]
+    pub fn parse(&self, s: &str) -> Result<Widget, Error> {
+        #[allow(unreachable_patterns)]
+        match self {
+            #[cfg(feature = "json")]
+            Format::Json => {
+                self.deserialize(serde_json::from_str(s).ok(), || serde_json::from_str(s))
+            }
+            // json is a subset of json5
+            #[cfg(all(not(feature = "json"), feature = "json5"))]
+            Format::Json => {
+                self.deserialize(serde_json5::from_str(s).ok(), || serde_json5::from_str(s))
+            }
+            #[cfg(feature = "json5")]
+            Format::Json5 => {
+                self.deserialize(serde_json5::from_str(s).ok(), || serde_json5::from_str(s))
+            }
+            #[cfg(feature = "yaml")]
+            Format::Yaml => {
+                self.deserialize(serde_yaml::from_str(s).ok(), || serde_yaml::from_str(s))
+            }
+            #[cfg(feature = "xml")]
+            Format::Xml => crate::xml_tree::parse(s),
+            #[cfg(feature = "toml")]
+            Format::Toml => self.deserialize(toml::from_str(s).ok(), || {
+                toml::from_str(s).map_err(|e| Error::from_toml(s, e))
+            }),
+            #[cfg(feature = "sexp")]
+            Format::Sexp => sexp(s),
+            #[cfg(feature = "ron")]
+            Format::Ron => {
+                let options = ron::Options::default()
+                    .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME);
+                self.deserialize(options.from_str(s).ok(), || options.from_str(s))
+            }
+            #[cfg(feature = "kdl")]
+            Format::Kdl => {
+                let doc: kdl::KdlDocument = s.parse()?;
+                match doc.nodes() {
+                    [node] => self.deserialize_value(kdl_node(node)),
+                    _ => Err(Error::parse("kdl", "expected a single root widget")),
+                }
+            }
+            _ => Err(Error::MissingFeature {
+                format: self.name(),
+                feature: self.feature(),
+            }),
+        }
+    }
+
+    /// Deserialize a serde format, going through a `Value` only if there's something to expand
+    #[allow(dead_code)]
+    fn deserialize<E: Into<Error>>(
+        &self,
+        value: Option<Value>,
+        widget: impl FnOnce() -> Result<Widget, E>,
+    ) -> Result<Widget, Error> {
+        match value {
+            Some(v) if !expand::is_plain(&v) => self.deserialize_value(v),
+            // deserializing from the source keeps the location of errors
+            Some(v) => {
+                let mut widget = widget().map_err(Into::into)?;
+                if let Ok((_, unknown)) = ignored(v) {
+                    for key in unknown {
+                        unknown_key(&mut widget, &key);
+                    }
+                }
+                widget.vars = expand::inherited();
+                Ok(widget)
+            }
+            None => widget().map_err(Into::into),
+        }
+    }
+
+    /// Expand a description's variables and components, then deserialize it into a widget
+    #[allow(dead_code)]
+    pub(crate) fn deserialize_value(&self, v: Value) -> Result<Widget, Error> {
+        let expanded = expand::expand(v)?;
+        let (mut widget, unknown) =
+            ignored(expanded.root).map_err(|e| Error::parse(self.name(), e))?;
+        for key in unknown {
+            unknown_key(&mut widget, &key);
+        }
+        for (path, name) in expanded.unresolved {
+            attach(&mut widget, &segments(&path), |w, _| {
+                w.unresolved.push(name)
+            });
+        }
+        widget.vars = expanded.vars;
+        Ok(widget)
+    }
+}
+
+/// A segment of the path to a key
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Deserialize a widget, along with the paths of the keys which aren't properties
+fn ignored(v: Value) -> Result<(Widget, Vec<Vec<Segment>>), crate::value::ValueError> {
+    let mut unknown = vec![];
+    let widget = serde_ignored::deserialize(v, |path| {
+        let mut segments = vec![];
+        let mut path = &path;
+        loop {
+            match path {
+                serde_ignored::Path::Root => break,
+                serde_ignored::Path::Seq { parent, index } => {
+                    segments.push(Segment::Index(*index));
+                    path = parent;
+                }
+                serde_ignored::Path::Map { parent, key } => {
+                    segments.push(Segment::Key(key.clone()));
+                    path = parent;
+                }
+                serde_ignored::Path::Some { parent }
+                | serde_ignored::Path::NewtypeStruct { parent }
+                | serde_ignored::Path::NewtypeVariant { parent } => path = parent,
+            }
+        }
+        segments.reverse();
+        // the json schema used by editors, ex. `"$schema": "../schemas/fltk-schema.json"`
+        if segments != [Segment::Key("$schema".to_string())] {
+            unknown.push(segments);
+        }
+    })?;
+    Ok((widget, unknown))
+}
+
+/// Run `record` on the widget a key belongs to, along with the rest of the key,
+/// ex. `lable` or `items[0].lable`
+fn attach(w: &mut Widget, key: &[Segment], record: impl FnOnce(&mut Widget, &[Segment])) {
+    if let [Segment::Key(list), Segment::Index(i), rest @ ..] = key {
+        let widgets = match list.as_str() {
+            "children" => w.children.as_mut(),
+            "windows" => w.windows.as_mut(),
+            _ => None,
+        };
+        if let Some(child) = widgets.and_then(|ws| ws.get_mut(*i)) {
+            return attach(child, rest, record);
+        }
+    }
+    record(w, key)
+}
+
+/// Record an unknown key on the widget it belongs to
+fn unknown_key(w: &mut Widget, key: &[Segment]) {
+    attach(w, key, |w, key| {
+        let mut name = String::new();
+        for segment in key {
+            match segment {
+                Segment::Key(k) if name.is_empty() => name.push_str(k),
+                Segment::Key(k) => name.push_str(&format!(".{k}")),
+                Segment::Index(i) => name.push_str(&format!("[{i}]")),
+            }
+        }
+        w.unknown.push(name);
+    });
+}
+
+/// The segments of a widget's path, ex. `children[1].children[0]`
+fn segments(path: &str) -> Vec<Segment> {
+    path.split('.')
+        .filter_map(|s| s.strip_suffix(']')?.split_once('['))
+        .flat_map(|(key, i)| {
+            [
+                Segment::Key(key.to_string()),
+                Segment::Index(i.parse().unwrap_or(0)),
+            ]
+        })
+        .collect()
+}
+
+/// Load a description, detecting the format from the file's extension, or otherwise its content
+pub(crate) fn load(path: &Path) -> Result<Widget, Error> {
+    let s = std::fs::read_to_string(path)?;
+    let format = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(Format::from_extension)
+        .or_else(|| Format::sniff(&s))
+        .ok_or_else(|| Error::UnknownFormat(path.to_path_buf()))?;
+    format.parse(&s)
+}
+
+/// Deserialize an s-expression directly, since it can't be read into a `Value`.
+/// Variables and components aren't supported, which is reported instead of failing on their references
+#[cfg(feature = "sexp")]
+fn sexp(s: &str) -> Result<Widget, Error> {
+    let value = serde_lexpr::parse::from_str(s).map_err(serde_lexpr::Error::from)?;
+    // the root is an alist, ex. ((widget . "Column") (vars (primary . "#0000ff")))
+    for entry in value.list_iter().into_iter().flatten() {
+        if let Some(key @ ("vars" | "constants" | "components")) =
+            entry.as_pair().and_then(|(key, _)| key.as_symbol())
+        {
+            return Err(Error::parse(
+                "s-expression",
+                format!("the {key} section isn't supported in s-expressions"),
+            ));
+        }
+    }
+    serde_lexpr::from_value(&value).map_err(|e| {
+        let mut e = Error::from(e);
+        if let (Error::Parse { message, .. }, Some(name)) = (&mut e, sexp_reference(&value)) {
+            message.push_str(&format!(
+                " (variables, ex. {name}, aren't supported in s-expressions)"
+            ));
+        }
+        e
+    })
+}
+
+/// The first string which looks like a reference to a variable, ex. `"$primary"`
+#[cfg(feature = "sexp")]
+fn sexp_reference(v: &serde_lexpr::Value) -> Option<&str> {
+    match v {
+        serde_lexpr::Value::String(s) if s.starts_with('$') && !s.starts_with("$$") => Some(s),
+        serde_lexpr::Value::Cons(pair) => {
+            sexp_reference(pair.car()).or_else(|| sexp_reference(pair.cdr()))
+        }
+        serde_lexpr::Value::Vector(values) => values.iter().find_map(sexp_reference),
+        _ => None,
+    }
+}
+
+/// Map a kdl node to a widget: the node name is the widget type, properties are widget properties,
+/// an argument is the label, and child nodes are the widget's children, except for `components`,
+/// `props`, `vars`, `window`, `windows`, `items`, `nodes`, `columns`, `entries`, `styles` and `rows` nodes,
+/// and lowercase nodes, whose argument is a property's value, or whose arguments make up a list
+#[cfg(feature = "kdl")]
+fn kdl_node(node: &kdl::KdlNode) -> Value {
+    let mut map = vec![(
+        "widget".to_string(),
+        Value::String(node.name().value().to_string()),
+    )];
+    map.extend(kdl_entries(node, "label"));
+    if let Some(children) = node.children() {
+        let mut widgets = vec![];
+        for child in children.nodes() {
+            match child.name().value() {
+                // components { LabeledInput { Row { .. } } }
+                "components" => {
+                    let components = child
+                        .children()
+                        .map(|c| c.nodes())
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|c| {
+                            let body = c
+                                .children()
+                                .and_then(|b| b.nodes().first())
+                                .map(kdl_node)
+                                .unwrap_or(Value::Null);
+                            (c.name().value().to_string(), body)
+                        })
+                        .collect();
+                    map.push(("components".to_string(), Value::Map(components)));
+                }
+                // windows { Column { window name="settings" modal=true; .. } }
+                "windows" => {
+                    let windows = child
+                        .children()
+                        .map(|c| c.nodes())
+                        .unwrap_or_default()
+                        .iter()
+                        .map(kdl_node)
+                        .collect();
+                    map.push(("windows".to_string(), Value::Seq(windows)));
+                }
+                // props label="Name" id="name", vars primary="#0000ff", or window title="App" w=400
+                name @ ("props" | "vars" | "constants" | "window") => {
+                    map.push((name.to_string(), Value::Map(kdl_entries(child, "label"))));
+                }
+                // items { item "File" { item "Open" shortcut="Ctrl+O" } },
+                // nodes { node "Settings" open=false { node "Display" } },
+                // columns { column "Age" width=60 }, entries { entry 3 label="Mon" },
+                // styles { style color="#0000ff" font=4 },
+                // or only their labels or values, ex. items "JAN" "FEB"
+                name @ ("items" | "nodes" | "columns" | "entries" | "styles") => {
+                    let entries = match child.children() {
+                        Some(c) => c.nodes().iter().map(|c| kdl_entry(c, name)).collect(),
+                        None => kdl_values(child),
+                    };
+                    map.push((name.to_string(), Value::Seq(entries)));
+                }
+                // rows "people", or rows { row "Alice" 31; row "Bob" 42 }
+                "rows" => {
+                    let rows = match child.children() {
+                        Some(rows) => Value::Seq(
+                            rows.nodes()
+                                .iter()
+                                .map(|row| Value::Seq(kdl_values(row)))
+                                .collect(),
+                        ),
+                        None => kdl_values(child).into_iter().next().unwrap_or(Value::Null),
+                    };
+                    map.push(("rows".to_string(), rows));
+                }
+                // a property, ex. text "hi", or a list, ex. column_widths 100 50.
+                // A single value is also read as a one-element list where one is expected
+                name if name.starts_with(|c: char| c.is_ascii_lowercase()) => {
+                    let mut values = kdl_values(child);
+                    let value = match values.len() {
+                        1 => values.remove(0),
+                        _ => Value::Seq(values),
+                    };
+                    map.push((name.to_string(), value));
+                }
+                _ => widgets.push(kdl_node(child)),
+            }
+        }
+        if !widgets.is_empty() {
+            map.push(("children".to_string(), Value::Seq(widgets)));
+        }
+    }
+    Value::Map(map)
+}
+
+/// Map a kdl node to a menu item, a tree node, a table column or a chart entry,
+/// whose child nodes are nested under `key`
+#[cfg(feature = "kdl")]
+fn kdl_entry(node: &kdl::KdlNode, key: &str) -> Value {
+    // the argument of a chart entry is its value, ex. entry 3 label="Mon"
+    let argument = if key == "entries" { "value" } else { "label" };
+    let mut map = kdl_entries(node, argument);
+    if let Some(children) = node.children() {
+        map.push((
+            key.to_string(),
+            Value::Seq(children.nodes().iter().map(|c| kdl_entry(c, key)).collect()),
+        ));
+    }
+    Value::Map(map)
+}
+
+/// The values of a node's arguments and properties, ex. for a list
+#[cfg(feature = "kdl")]
+fn kdl_values(node: &kdl::KdlNode) -> Vec<Value> {
+    kdl_entries(node, "label")
+        .into_iter()
+        .map(|(_, v)| v)
+        .collect()
+}
+
+/// A node's properties, with its argument under the `argument` key
+#[cfg(feature = "kdl")]
+fn kdl_entries(node: &kdl::KdlNode, argument: &str) -> Vec<(String, Value)> {
+    node.entries()
+        .iter()
+        .map(|entry| {
+            let value = match entry.value() {
+                kdl::KdlValue::RawString(s) | kdl::KdlValue::String(s) => Value::String(s.clone()),
+                kdl::KdlValue::Base2(v)
+                | kdl::KdlValue::Base8(v)
+                | kdl::KdlValue::Base10(v)
+                | kdl::KdlValue::Base16(v) => Value::Int(*v),
+                kdl::KdlValue::Base10Float(v) => Value::Float(*v),
+                kdl::KdlValue::Bool(v) => Value::Bool(*v),
+                kdl::KdlValue::Null => Value::Null,
+            };
+            let key = match entry.name() {
+                Some(name) => name.value().to_string(),
+                None => argument.to_string(),
+            };
+            (key, value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{validate, DiagnosticKind};
+
+    fn unknown_keys(s: &str) -> Vec<(String, String)> {
+        let widget = Format::Json5
+            .deserialize_value(serde_json5::from_str(s).unwrap())
+            .unwrap();
+        validate(&widget)
+            .into_iter()
+            .filter_map(|d| match d.kind {
+                DiagnosticKind::UnknownKey(key) => Some((d.path, key)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unknown_keys_are_reported() {
+        let keys = unknown_keys(
+            r##"{
+                "$schema": "../schemas/fltk-schema.json",
+                "widget": "Column",
+                "labelcolour": "#ff0000",
+                "children": [
+                    { "widget": "Button", "lable": "Click" },
+                    { "widget": "MenuBar", "items": [{ "label": "File", "callbak": "open" }] },
+                ],
+                "windows": [{ "widget": "Row", "window": { "name": "about" }, "hide_me": true }],
+            }"##,
+        );
+        assert_eq!(
+            keys,
+            [
+                ("".to_string(), "labelcolour".to_string()),
+                ("children[0]".to_string(), "lable".to_string()),
+                ("children[1]".to_string(), "items[0].callbak".to_string()),
+                ("windows[0]".to_string(), "hide_me".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn known_keys_are_not_reported() {
+        let keys = unknown_keys(
+            r##"{ "vars": { "c": "#0000ff" }, "widget": "Column", "color": "$c", "children": [] }"##,
+        );
+        assert!(keys.is_empty(), "{keys:?}");
+    }
+
+    /// Parse a json5 description the way the json5 feature does
+    fn parse(s: &str) -> Result<Widget, Error> {
+        Format::Json5.deserialize(serde_json5::from_str(s).ok(), || {
+            serde_json5::from_str(s).map_err(|e| Error::parse("json5", e))
+        })
+    }
+
+    #[test]
+    fn escapes_are_resolved_without_vars() {
+        let widget = parse(r#"{ "widget": "Button", "label": "$$5" }"#).unwrap();
+        assert_eq!(widget.label.as_deref(), Some("$5"));
+        let widget = parse(r#"{ "widget": "Button", "label": "5$" }"#).unwrap();
+        assert_eq!(widget.label.as_deref(), Some("5$"));
+    }
+
+    #[test]
+    fn formats_are_sniffed() {
+        let json = if cfg!(feature = "json5") {
+            Format::Json5
+        } else {
+            Format::Json
+        };
+        for (s, format) in [
+            ("{\"widget\": \"Column\"}", Some(json)),
+            ("// a comment\n{widget: 'Column'}", Some(json)),
+            ("<Column label=\"Hi\"/>", Some(Format::Xml)),
+            ("# a comment\nwidget: Column", Some(Format::Yaml)),
+            ("---\nwidget: Column", Some(Format::Yaml)),
+            ("widget = \"Column\"", Some(Format::Toml)),
+            ("[[children]]\nwidget = \"Button\"", Some(Format::Toml)),
+            ("((widget . \"Column\"))", Some(Format::Sexp)),
+            ("; a comment\n((widget . \"Column\"))", Some(Format::Sexp)),
+            ("(widget: \"Column\")", Some(Format::Ron)),
+            ("Widget(widget: \"Column\")", Some(Format::Ron)),
+            ("Column {\n    Button \"Click\"\n}", Some(Format::Kdl)),
+            ("Button \"Click\"", Some(Format::Kdl)),
+            ("  \n# only a comment", None),
+        ] {
+            assert_eq!(Format::sniff(s), format, "{s}");
+        }
+    }
+
+    #[test]
+    fn unknown_variables_are_kept_and_reported() {
+        let widget = parse(
+            r#"{ "widget": "Column", "children": [{ "widget": "Button", "label": "$Total" }] }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            widget.children.as_ref().unwrap()[0].label.as_deref(),
+            Some("$Total")
+        );
+        assert_eq!(
+            validate(&widget),
+            [crate::Diagnostic {
+                path: "children[0]".to_string(),
+                kind: DiagnosticKind::UnknownVariable("Total".to_string()),
+            }]
+        );
+    }
+
+    #[cfg(feature = "kdl")]
+    fn kdl(s: &str) -> Value {
+        let doc: kdl::KdlDocument = s.parse().unwrap();
+        kdl_node(&doc.nodes()[0])
+    }
+
+    #[cfg(feature = "kdl")]
+    fn string(s: &str) -> Value {
+        Value::String(s.to_string())
+    }
+
+    #[test]
+    #[cfg(feature = "kdl")]
+    fn kdl_nodes_are_widgets() {
+        assert_eq!(
+            kdl(r#"Column margin=10 {
+                Button "Inc" fixed=60
+                Input
+            }"#),
+            Value::Map(vec![
+                ("widget".to_string(), string("Column")),
+                ("margin".to_string(), Value::Int(10)),
+                (
+                    "children".to_string(),
+                    Value::Seq(vec![
+                        Value::Map(vec![
+                            ("widget".to_string(), string("Button")),
+                            ("label".to_string(), string("Inc")),
+                            ("fixed".to_string(), Value::Int(60)),
+                        ]),
+                        Value::Map(vec![("widget".to_string(), string("Input"))]),
+                    ])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "kdl")]
+    fn kdl_child_nodes_are_properties() {
+        let v = kdl(r#"TextEditor {
+                text "hi"
+                column_widths 100 50
+                angles 90
+                window title="Editor" w=400
+                items { item "File" { item "Open" shortcut="^o"; }; }
+                rows { row "Alice" 31; }
+            }"#);
+        assert_eq!(v.get("text"), Some(&string("hi")));
+        assert_eq!(
+            v.get("column_widths"),
+            Some(&Value::Seq(vec![Value::Int(100), Value::Int(50)]))
+        );
+        assert_eq!(v.get("angles"), Some(&Value::Int(90)));
+        assert_eq!(
+            v.get("window"),
+            Some(&Value::Map(vec![
+                ("title".to_string(), string("Editor")),
+                ("w".to_string(), Value::Int(400)),
+            ]))
+        );
+        assert_eq!(
+            v.get("items"),
+            Some(&Value::Seq(vec![Value::Map(vec![
+                ("label".to_string(), string("File")),
+                (
+                    "items".to_string(),
+                    Value::Seq(vec![Value::Map(vec![
+                        ("label".to_string(), string("Open")),
+                        ("shortcut".to_string(), string("^o")),
+                    ])])
+                ),
+            ])]))
+        );
+        assert_eq!(
+            v.get("rows"),
+            Some(&Value::Seq(vec![Value::Seq(vec![
+                string("Alice"),
+                Value::Int(31)
+            ])]))
+        );
+        assert_eq!(v.get("children"), None);
+
+        let widget = Format::Kdl
+            .parse(r#"Dial { text "hi"; angles 90; }"#)
+            .unwrap();
+        assert_eq!(widget.text.as_deref(), Some("hi"));
+        assert_eq!(widget.angles, Some(vec![90]));
+    }
+
+    #[test]
+    #[cfg(feature = "kdl")]
+    fn kdl_sections_are_expanded() {
+        let widget = Format::Kdl
+            .parse(
+                r#"Column {
+                    vars height=30
+                    components { LabeledInput { Row fixed="$height" { Frame "{{label}}"; Input; }; }; }
+                    LabeledInput { props label="Name"; }
+                    windows { Row { window name="settings"; Button "Ok"; }; }
+                }"#,
+            )
+            .unwrap();
+        let row = &widget.children.as_ref().unwrap()[0];
+        assert_eq!(row.fixed, Some(crate::items::Size::Pixels(30)));
+        assert_eq!(
+            row.children.as_ref().unwrap()[0].label.as_deref(),
+            Some("Name")
+        );
+        let windows = widget.windows.unwrap();
+        assert_eq!(
+            windows[0].window.as_ref().unwrap().name.as_deref(),
+            Some("settings")
+        );
+        assert_eq!(
+            windows[0].children.as_ref().unwrap()[0].label.as_deref(),
+            Some("Ok")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "sexp")]
+    fn sexp_sections_are_reported() {
+        let message = |s: &str| match Format::Sexp.parse(s) {
+            Err(Error::Parse { message, .. }) => message,
+            r => panic!("{r:?}"),
+        };
+        assert!(message(r#"((widget . "Column") (vars (h . 30)))"#).contains("the vars section"));
+        assert!(
+            message(r#"((widget . "Column") (components (Big (widget . "Button"))))"#)
+                .contains("the components section")
+        );
+        assert!(message(r#"((widget . "Column") (fixed "$h"))"#).contains("ex. $h"));
+        let widget = Format::Sexp
+            .parse(r#"((widget . "Column") (label "$5"))"#)
+            .unwrap();
+        assert_eq!(widget.label.as_deref(), Some("$5"));
+    }
+}
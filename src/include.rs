@@ -0,0 +1,194 @@
+use crate::{expand, value::Value, Error, Loader, Widget};
+use std::path::{Path, PathBuf};
+
+/// Load a description and resolve its includes.
+/// The included files are pushed to `files`, even if loading one of them fails, so that they can be watched
+pub(crate) fn load(
+    loader: &dyn Loader,
+    path: &Path,
+    files: &mut Vec<PathBuf>,
+) -> Result<Widget, Error> {
+    let widget = loader.load(path)?;
+    let mut stack = vec![canonical(path)];
+    let vars = widget.vars.clone();
+    resolve(widget, path, &vars, loader, &mut stack, files)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The file included by a node, either through the `include` property or an `Include` widget's `src`
+fn source(w: &mut Widget) -> Option<String> {
+    w.include.take().or_else(|| {
+        if w.widget == "Include" {
+            w.src.take()
+        } else {
+            None
+        }
+    })
+}
+
+/// Resolve a node's includes, where `vars` are the variables of the file declaring it,
+/// which are visible to the included files
+fn resolve(
+    mut w: Widget,
+    path: &Path,
+    vars: &[(String, Value)],
+    loader: &dyn Loader,
+    stack: &mut Vec<PathBuf>,
+    files: &mut Vec<PathBuf>,
+) -> Result<Widget, Error> {
+    if let Some(src) = source(&mut w) {
+        let inc = path.parent().unwrap_or(Path::new("")).join(src);
+        let canon = canonical(&inc);
+        if stack.contains(&canon) {
+            return Err(Error::IncludeCycle(inc));
+        }
+        if !files.contains(&inc) {
+            files.push(inc.clone());
+        }
+        let included =
+            expand::with_vars(vars, || loader.load(&inc)).map_err(|e| Error::Include {
+                path: inc.clone(),
+                error: Box::new(e),
+            })?;
+        stack.push(canon);
+        let inc_vars = included.vars.clone();
+        let mut included = resolve(included, &inc, &inc_vars, loader, stack, files)?;
+        stack.pop();
+        // the placement in the parent is decided by the including node
+        macro_rules! placement {
+            ($($field: ident),*) => {
+                $(
+                    if w.$field.is_some() {
+                        included.$field = w.$field;
+                    }
+                )*
+            };
+        }
+        placement!(id, fixed, weight, min, max, x, y, w, h, row, col, rowspan, colspan, alignment);
+        included.unknown.extend(w.unknown);
+        included.unresolved.extend(w.unresolved);
+        if w.children.is_some() {
+            included.dropped.push("children");
+        }
+        if w.windows.is_some() {
+            included.dropped.push("windows");
+        }
+        return Ok(included);
+    }
+    if let Some(children) = w.children.take() {
+        w.children = Some(
+            children
+                .into_iter()
+                .map(|c| resolve(c, path, vars, loader, stack, files))
+                .collect::<Result<_, _>>()?,
+        );
+    }
+    if let Some(windows) = w.windows.take() {
+        w.windows = Some(
+            windows
+                .into_iter()
+                .map(|c| resolve(c, path, vars, loader, stack, files))
+                .collect::<Result<_, _>>()?,
+        );
+    }
+    Ok(w)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Format;
+
+    /// A loader reading json5 descriptions from memory
+    fn loader(files: &'static [(&'static str, &'static str)]) -> impl Loader {
+        move |path: &Path| {
+            let (_, s) = files
+                .iter()
+                .find(|(name, _)| Path::new(name) == path)
+                .ok_or_else(|| Error::UnknownFormat(path.to_path_buf()))?;
+            Format::Json5.deserialize_value(serde_json5::from_str(s).unwrap())
+        }
+    }
+
+    #[test]
+    fn vars_are_visible_to_included_files() {
+        let loader = loader(&[
+            (
+                "main.json",
+                r##"{ "vars": { "primary": "#0000ff" }, "widget": "Column", "children": [{ "include": "row.json" }] }"##,
+            ),
+            (
+                "row.json",
+                r#"{ "widget": "Row", "children": [{ "include": "button.json" }] }"#,
+            ),
+            (
+                "button.json",
+                r#"{ "vars": { "label": "Ok" }, "widget": "Button", "label": "$label", "color": "$primary" }"#,
+            ),
+        ]);
+        let widget = load(&loader, Path::new("main.json"), &mut vec![]).unwrap();
+        let button = &widget.children.unwrap()[0].children.clone().unwrap()[0];
+        assert_eq!(button.label.as_deref(), Some("Ok"));
+        assert_eq!(button.color.as_deref(), Some("#0000ff"));
+    }
+
+    #[test]
+    fn include_cycles_are_detected() {
+        let loader = loader(&[
+            (
+                "main.json",
+                r#"{ "widget": "Column", "children": [{ "include": "a.json" }] }"#,
+            ),
+            (
+                "a.json",
+                r#"{ "widget": "Row", "children": [{ "widget": "Include", "src": "b.json" }] }"#,
+            ),
+            (
+                "b.json",
+                r#"{ "widget": "Row", "children": [{ "include": "a.json" }] }"#,
+            ),
+        ]);
+        let mut files = vec![];
+        let err = load(&loader, Path::new("main.json"), &mut files).unwrap_err();
+        assert!(
+            matches!(&err, Error::IncludeCycle(path) if path == Path::new("a.json")),
+            "{err}"
+        );
+        // the files are watched even though loading failed
+        assert_eq!(files, [PathBuf::from("a.json"), PathBuf::from("b.json")]);
+        // including the same file twice isn't a cycle
+        let loader = self::loader(&[
+            (
+                "main.json",
+                r#"{ "widget": "Column", "children": [{ "include": "a.json" }, { "include": "a.json" }] }"#,
+            ),
+            ("a.json", r#"{ "widget": "Button" }"#),
+        ]);
+        assert!(load(&loader, Path::new("main.json"), &mut vec![]).is_ok());
+    }
+
+    #[test]
+    fn children_of_include_nodes_are_reported() {
+        let loader = loader(&[
+            (
+                "main.json",
+                r#"{ "widget": "Column", "children": [{ "include": "row.json", "children": [{ "widget": "Button" }] }] }"#,
+            ),
+            ("row.json", r#"{ "widget": "Row" }"#),
+        ]);
+        let widget = load(&loader, Path::new("main.json"), &mut vec![]).unwrap();
+        assert_eq!(
+            crate::validate(&widget),
+            [crate::Diagnostic {
+                path: "children[0]".to_string(),
+                kind: crate::DiagnosticKind::InapplicableProperty {
+                    property: "children",
+                    widget: "Include".to_string(),
+                },
+            }]
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use crate::WidgetValue;
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::{Serialize, Serializer};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+/// An entry of a menu or a browser, written either as its label or as a map of properties
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub(crate) struct Item {
+    #[serde(default)]
+    pub(crate) label: String,
+    pub(crate) shortcut: Option<String>,
+    pub(crate) toggle: Option<bool>,
+    pub(crate) radio: Option<bool>,
+    pub(crate) divider: Option<bool>,
+    pub(crate) inactive: Option<bool>,
+    /// Whether a toggle or radio menu item, or a CheckBrowser line, is checked
+    #[serde(alias = "checked")]
+    pub(crate) value: Option<bool>,
+    pub(crate) callback: Option<String>,
+    /// The browser format codes prefixing the line, ex. `@b@C1`
+    pub(crate) format: Option<String>,
+    pub(crate) icon: Option<String>,
+    pub(crate) selected: Option<bool>,
+    #[serde(alias = "children")]
+    pub(crate) items: Option<Vec<Item>>,
+}
+
+impl Item {
+    /// The text of a browser line, along with its format codes
+    pub(crate) fn line(&self) -> String {
+        match &self.format {
+            Some(format) => format!("{format}{}", self.label),
+            None => self.label.clone(),
+        }
+    }
+}
+
+/// A node of a Tree, written either as its label or as a map of properties.
+/// The label can be a path, ex. `Settings/Display`, creating the intermediate nodes
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub(crate) struct Node {
+    #[serde(default, alias = "path")]
+    pub(crate) label: String,
+    pub(crate) open: Option<bool>,
+    pub(crate) icon: Option<String>,
+    /// A string stored as the node's user data
+    pub(crate) data: Option<String>,
+    pub(crate) selected: Option<bool>,
+    #[serde(alias = "children")]
+    pub(crate) nodes: Option<Vec<Node>>,
+}
+
+/// A column of a Table, written either as its header label or as a map of properties
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub(crate) struct Column {
+    #[serde(default)]
+    pub(crate) label: String,
+    pub(crate) width: Option<i32>,
+    /// The alignment of the column's cells
+    pub(crate) align: Option<i32>,
+}
+
+/// The rows of a Table, either declared or provided by a registered data source,
+/// or the number of rows of a Grid or of an empty Table
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Rows {
+    Count(i32),
+    Source(String),
+    Data(Vec<Vec<WidgetValue>>),
+}
+
+impl Rows {
+    /// The number of rows, also when written as a string, ex. in xml
+    pub(crate) fn count(&self) -> Option<i32> {
+        match self {
+            Rows::Count(count) => Some(*count),
+            Rows::Source(s) => s.trim().parse().ok(),
+            Rows::Data(_) => None,
+        }
+    }
+}
+
+/// The size of a Flex's child along the Flex's direction, in pixels, ex. `60`,
+/// or as a percentage of the space available to the children, ex. `"25%"`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Size {
+    Pixels(i32),
+    Percent(f64),
+}
+
+impl Size {
+    /// The size in pixels, resolving a percentage against the `available` space
+    pub(crate) fn resolve(&self, available: i32) -> i32 {
+        match self {
+            Size::Pixels(px) => *px,
+            Size::Percent(p) => (available as f64 * p / 100.0).round() as i32,
+        }
+    }
+}
+
+struct SizeVisitor;
+
+impl<'de> Visitor<'de> for SizeVisitor {
+    type Value = Size;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a number of pixels or a percentage, ex. \"25%\"")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Size, E> {
+        Ok(Size::Pixels(v as i32))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Size, E> {
+        Ok(Size::Pixels(v as i32))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Size, E> {
+        Ok(Size::Pixels(v.round() as i32))
+    }
+
+    // also pixels written as strings, ex. in xml
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Size, E> {
+        let s = v.trim();
+        let size = match s.strip_suffix('%') {
+            Some(p) => p.trim().parse().ok().map(Size::Percent),
+            None => s.parse().ok().map(Size::Pixels),
+        };
+        size.ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+
+    // the text of an element, ex. `<fixed>60</fixed>` in the older `<root>` xml shape
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Size, A::Error> {
+        match map.next_entry::<String, Size>()? {
+            Some((key, size)) if key == "$value" => Ok(size),
+            _ => Err(de::Error::invalid_type(de::Unexpected::Map, &self)),
+        }
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Size {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_any(SizeVisitor)
+    }
+}
+
+impl Serialize for Size {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Size::Pixels(px) => s.serialize_i32(*px),
+            Size::Percent(p) => s.serialize_str(&format!("{p}%")),
+        }
+    }
+}
+
+/// A value of a Chart, written either as the value or as a map of properties
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub(crate) struct Entry {
+    #[serde(default)]
+    pub(crate) value: f64,
+    #[serde(default)]
+    pub(crate) label: String,
+    pub(crate) color: Option<String>,
+}
+
+struct EntryVisitor;
+
+impl<'de> Visitor<'de> for EntryVisitor {
+    type Value = Entry;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a number or a map of entry properties")
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Entry, E> {
+        Ok(Entry {
+            value,
+            ..Default::default()
+        })
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Entry, E> {
+        self.visit_f64(v as f64)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Entry, E> {
+        self.visit_f64(v as f64)
+    }
+
+    // formats without numbers, ex. xml
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Entry, E> {
+        v.trim()
+            .parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+            .and_then(|v| self.visit_f64(v))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Entry, A::Error> {
+        Entry::deserialize(de::value::MapAccessDeserializer::new(map))
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Entry {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_any(EntryVisitor)
+    }
+}
+
+impl Serialize for Entry {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        Entry::serialize(self, s)
+    }
+}
+
+/// An entry of a text widget's style table, used by the style letter of its position, ex. `A` for the first.
+/// Unset fields default to the widget's text color, font and size
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Style {
+    pub(crate) color: Option<String>,
+    pub(crate) font: Option<i32>,
+    pub(crate) size: Option<i32>,
+}
+
+/// Deserialize an entry from either its label or a map of its properties
+macro_rules! label_or_map {
+    ($ty: ident, $visitor: ident, $expecting: literal) => {
+        struct $visitor;
+
+        impl<'de> Visitor<'de> for $visitor {
+            type Value = $ty;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str($expecting)
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<$ty, E> {
+                Ok($ty {
+                    label: v.to_string(),
+                    ..Default::default()
+                })
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<$ty, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<$ty, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<$ty, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<$ty, A::Error> {
+                $ty::deserialize(de::value::MapAccessDeserializer::new(map))
+            }
+        }
+
+        impl<'de> de::Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                d.deserialize_any($visitor)
+            }
+        }
+
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                $ty::serialize(self, s)
+            }
+        }
+    };
+}
+
+label_or_map!(Item, ItemVisitor, "a label or a map of item properties");
+label_or_map!(Node, NodeVisitor, "a label or a map of node properties");
+label_or_map!(
+    Column,
+    ColumnVisitor,
+    "a label or a map of column properties"
+);
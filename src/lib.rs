@@ -7,22 +7,102 @@ use notify::{
 };
 use serde_derive::{Deserialize, Serialize};
 use std::{
-    path::PathBuf,
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    rc::Rc,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
 };
 
+mod error;
+mod expand;
+mod formats;
 mod frames;
+mod include;
+mod items;
+mod loader;
+mod overlay;
+mod toplevel;
 mod utils;
+mod validate;
+mod value;
+#[cfg(feature = "xml")]
+mod xml_tree;
+
+pub use error::Error;
+pub use formats::Format;
+pub use loader::Loader;
+pub use validate::{validate, Diagnostic, DiagnosticKind, Strictness};
+#[cfg(feature = "xml")]
+pub use xml_tree::xml_schema;
+
+/// A callback which can be bound to widgets by name from the GUI file
+pub type Callback = Rc<dyn Fn(&mut widget::Widget)>;
+
+/// A function providing the rows of a Table, which can be bound by name from the GUI file
+pub type DataSource = Rc<dyn Fn() -> Vec<Vec<String>>>;
+
+/// Add a value to the Chart whose id is `id`, returning whether the chart was found.
+/// Useful to stream values into a chart at runtime, ex. from a timeout
+pub fn add_chart_value(id: &str, value: f64, label: &str, color: enums::Color) -> bool {
+    match app::widget_from_id::<misc::Chart>(id) {
+        Some(mut chart) => {
+            chart.add(value, label, color);
+            chart.redraw();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Show the window whose `window` section is named `name`, returning whether it was found.
+/// Useful to open a dialog declared in the `windows` section, ex. from a callback
+pub fn show_window(name: &str) -> bool {
+    match app::widget_from_id::<window::Window>(name) {
+        Some(mut win) if !win.was_deleted() => {
+            win.show();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Hide the window whose `window` section is named `name`, returning whether it was found
+pub fn hide_window(name: &str) -> bool {
+    match app::widget_from_id::<window::Window>(name) {
+        Some(mut win) if !win.was_deleted() => {
+            win.hide();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// A function styling the text of a TextDisplay or TextEditor, which can be bound by name from the GUI file.
+/// It returns the style letter of every byte of the text, ex. `A` for the first entry of the `styles` table
+pub type Highlighter = Rc<dyn Fn(&str) -> String>;
+
+/// The callbacks, data sources and highlighters registered on the app
+#[derive(Clone, Default)]
+pub(crate) struct Registry {
+    callbacks: HashMap<String, Callback>,
+    sources: HashMap<String, DataSource>,
+    highlighters: HashMap<String, Highlighter>,
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Widget {
+    #[serde(default)]
     widget: String,
     label: Option<String>,
     id: Option<String>,
-    fixed: Option<i32>,
+    fixed: Option<items::Size>,
+    weight: Option<i32>,
+    min: Option<items::Size>,
+    max: Option<items::Size>,
     color: Option<String>,
     labelcolor: Option<String>,
     children: Option<Vec<Widget>>,
@@ -46,6 +126,45 @@ pub struct Widget {
     maximum: Option<f64>,
     step: Option<f64>,
     slidersize: Option<f64>,
+    value: Option<WidgetValue>,
+    precision: Option<i32>,
+    lstep: Option<f64>,
+    angles: Option<Vec<i32>>,
+    items: Option<Vec<items::Item>>,
+    column_widths: Option<Vec<i32>>,
+    column_char: Option<String>,
+    nodes: Option<Vec<items::Node>>,
+    showroot: Option<bool>,
+    connectorstyle: Option<String>,
+    selectmode: Option<String>,
+    columns: Option<Vec<items::Column>>,
+    rows: Option<items::Rows>,
+    rowheader: Option<bool>,
+    colheader: Option<bool>,
+    rowresize: Option<bool>,
+    colresize: Option<bool>,
+    charttype: Option<String>,
+    bounds: Option<Vec<f64>>,
+    autosize: Option<bool>,
+    maxsize: Option<i32>,
+    entries: Option<Vec<items::Entry>>,
+    text: Option<String>,
+    textfile: Option<String>,
+    wrap: Option<String>,
+    wrapmargin: Option<i32>,
+    linenumbers: Option<i32>,
+    styles: Option<Vec<items::Style>>,
+    highlight: Option<String>,
+    cols: Option<i32>,
+    row: Option<i32>,
+    col: Option<i32>,
+    rowspan: Option<i32>,
+    colspan: Option<i32>,
+    alignment: Option<String>,
+    rowweights: Option<Vec<i32>>,
+    colweights: Option<Vec<i32>>,
+    rowgap: Option<i32>,
+    colgap: Option<i32>,
     textfont: Option<i32>,
     textsize: Option<i32>,
     textcolor: Option<String>,
@@ -58,77 +177,254 @@ pub struct Widget {
     top: Option<i32>,
     right: Option<i32>,
     bottom: Option<i32>,
+    callback: Option<String>,
+    include: Option<String>,
+    src: Option<String>,
+    window: Option<toplevel::Toplevel>,
+    windows: Option<Vec<Widget>>,
+    /// The keys which aren't properties, dropped while deserializing and reported by `validate`
+    #[serde(skip)]
+    unknown: Vec<String>,
+    /// The variables visible to the files included by this description
+    #[serde(skip)]
+    vars: Vec<(String, value::Value)>,
+    /// The `$name` references to undeclared variables, kept as is and reported by `validate`
+    #[serde(skip)]
+    unresolved: Vec<String>,
+    /// The properties of an include node which the included widget doesn't take, ex. `children`,
+    /// dropped while loading and reported by `validate`
+    #[serde(skip)]
+    dropped: Vec<&'static str>,
+}
+
+/// A widget's value, whose meaning depends on the widget type
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum WidgetValue {
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl WidgetValue {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            WidgetValue::Bool(v) => Some(if *v { 1.0 } else { 0.0 }),
+            WidgetValue::Number(v) => Some(*v),
+            WidgetValue::Text(s) => s.trim().parse().ok(),
+        }
+    }
+
+    fn as_bool(&self) -> bool {
+        match self {
+            WidgetValue::Bool(v) => *v,
+            WidgetValue::Number(v) => *v != 0.0,
+            WidgetValue::Text(s) => matches!(s.trim(), "true" | "1"),
+        }
+    }
+
+    fn to_text(&self) -> String {
+        match self {
+            WidgetValue::Bool(v) => v.to_string(),
+            WidgetValue::Number(v) => v.to_string(),
+            WidgetValue::Text(s) => s.clone(),
+        }
+    }
+}
+
+impl Widget {
+    /// Deserialize a json description, useful with `DeclarativeApp::new_inline`
+    #[cfg(feature = "json")]
+    pub fn from_json(s: &str) -> Result<Widget, Error> {
+        Format::Json.parse(s)
+    }
+
+    /// Deserialize a json5 description, useful with `DeclarativeApp::new_inline`
+    #[cfg(feature = "json5")]
+    pub fn from_json5(s: &str) -> Result<Widget, Error> {
+        Format::Json5.parse(s)
+    }
+
+    /// Deserialize a yaml description, useful with `DeclarativeApp::new_inline`
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(s: &str) -> Result<Widget, Error> {
+        Format::Yaml.parse(s)
+    }
+
+    /// Deserialize an xml description, useful with `DeclarativeApp::new_inline`
+    #[cfg(feature = "xml")]
+    pub fn from_xml(s: &str) -> Result<Widget, Error> {
+        Format::Xml.parse(s)
+    }
+
+    /// Deserialize a toml description, useful with `DeclarativeApp::new_inline`
+    #[cfg(feature = "toml")]
+    pub fn from_toml(s: &str) -> Result<Widget, Error> {
+        Format::Toml.parse(s)
+    }
+
+    /// Deserialize an s-expression description, useful with `DeclarativeApp::new_inline`
+    #[cfg(feature = "sexp")]
+    pub fn from_sexp(s: &str) -> Result<Widget, Error> {
+        Format::Sexp.parse(s)
+    }
+
+    /// Deserialize a ron description, useful with `DeclarativeApp::new_inline`
+    #[cfg(feature = "ron")]
+    pub fn from_ron(s: &str) -> Result<Widget, Error> {
+        Format::Ron.parse(s)
+    }
+
+    /// Deserialize a kdl description, useful with `DeclarativeApp::new_inline`
+    #[cfg(feature = "kdl")]
+    pub fn from_kdl(s: &str) -> Result<Widget, Error> {
+        Format::Kdl.parse(s)
+    }
 }
 
 /// Entry point for your declarative app
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct DeclarativeApp {
     a: app::App,
     w: i32,
     h: i32,
     label: String,
     #[allow(dead_code)]
-    path: Option<&'static str>,
+    path: Option<PathBuf>,
     widget: Option<Widget>,
-    load_fn: fn(&'static str) -> Option<Widget>,
+    includes: Vec<PathBuf>,
+    loader: Rc<dyn Loader>,
+    registry: Registry,
+    error_overlay: bool,
+    keep_values: bool,
+    strictness: Strictness,
+}
+
+impl fmt::Debug for DeclarativeApp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeclarativeApp")
+            .field("a", &self.a)
+            .field("w", &self.w)
+            .field("h", &self.h)
+            .field("label", &self.label)
+            .field("path", &self.path)
+            .field("widget", &self.widget)
+            .field("includes", &self.includes)
+            .field(
+                "callbacks",
+                &self.registry.callbacks.keys().collect::<Vec<_>>(),
+            )
+            .field("sources", &self.registry.sources.keys().collect::<Vec<_>>())
+            .field(
+                "highlighters",
+                &self.registry.highlighters.keys().collect::<Vec<_>>(),
+            )
+            .field("error_overlay", &self.error_overlay)
+            .field("keep_values", &self.keep_values)
+            .field("strictness", &self.strictness)
+            .finish_non_exhaustive()
+    }
 }
 
 impl DeclarativeApp {
-    /// Instantiate a new declarative app
+    /// Instantiate a new declarative app.
+    /// The size and label of the main window can be overridden by the gui file's `window` section.
+    /// Included files are loaded with the same loader, relative to the including file.
+    /// Fails if the gui file or one of its includes can't be loaded
     pub fn new(
         w: i32,
         h: i32,
         label: &str,
-        path: &'static str,
-        load_fn: fn(&'static str) -> Option<Widget>,
-    ) -> Self {
-        let widget = load_fn(path);
+        path: impl Into<PathBuf>,
+        loader: impl Loader + 'static,
+    ) -> Result<Self, Error> {
+        let path = path.into();
+        let mut includes = vec![];
+        let widget = include::load(&loader, &path, &mut includes)?;
         let a = app::App::default().with_scheme(app::Scheme::Gtk);
-        Self {
+        Ok(Self {
             a,
             w,
             h,
             label: label.to_string(),
             path: Some(path),
-            widget,
-            load_fn,
-        }
+            widget: Some(widget),
+            includes,
+            loader: Rc::new(loader),
+            registry: Registry::default(),
+            error_overlay: false,
+            keep_values: false,
+            strictness: Strictness::Permissive,
+        })
+    }
+
+    /// Instantiate a new declarative app, detecting the gui file's format from its extension,
+    /// or otherwise from its content.
+    /// Fails if the format's feature isn't enabled
+    pub fn from_file(w: i32, h: i32, label: &str, path: impl Into<PathBuf>) -> Result<Self, Error> {
+        Self::new(w, h, label, path, formats::load)
     }
 
     #[cfg(feature = "json")]
-    pub fn new_json(w: i32, h: i32, label: &str, path: &'static str) -> Self {
-        fn load_fn(path: &'static str) -> Option<Widget> {
-            let s = std::fs::read_to_string(path).ok()?;
-            serde_json::from_str(&s).map_err(|e| eprintln!("{e}")).ok()
+    pub fn new_json(w: i32, h: i32, label: &str, path: impl Into<PathBuf>) -> Result<Self, Error> {
+        fn load_fn(path: &Path) -> Result<Widget, Error> {
+            Format::Json.parse(&std::fs::read_to_string(path)?)
         }
         Self::new(w, h, label, path, load_fn)
     }
 
     #[cfg(feature = "json5")]
-    pub fn new_json5(w: i32, h: i32, label: &str, path: &'static str) -> Self {
-        fn load_fn(path: &'static str) -> Option<Widget> {
-            let s = std::fs::read_to_string(path).ok()?;
-            serde_json5::from_str(&s).map_err(|e| eprintln!("{e}")).ok()
+    pub fn new_json5(w: i32, h: i32, label: &str, path: impl Into<PathBuf>) -> Result<Self, Error> {
+        fn load_fn(path: &Path) -> Result<Widget, Error> {
+            Format::Json5.parse(&std::fs::read_to_string(path)?)
         }
         Self::new(w, h, label, path, load_fn)
     }
 
     #[cfg(feature = "xml")]
-    pub fn new_xml(w: i32, h: i32, label: &str, path: &'static str) -> Self {
-        fn load_fn(path: &'static str) -> Option<Widget> {
-            let s = std::fs::read_to_string(path).ok()?;
-            serde_xml_rs::from_str(&s)
-                .map_err(|e| eprintln!("{e}"))
-                .ok()
+    pub fn new_xml(w: i32, h: i32, label: &str, path: impl Into<PathBuf>) -> Result<Self, Error> {
+        fn load_fn(path: &Path) -> Result<Widget, Error> {
+            Format::Xml.parse(&std::fs::read_to_string(path)?)
         }
         Self::new(w, h, label, path, load_fn)
     }
 
     #[cfg(feature = "yaml")]
-    pub fn new_yaml(w: i32, h: i32, label: &str, path: &'static str) -> Self {
-        fn load_fn(path: &'static str) -> Option<Widget> {
-            let s = std::fs::read_to_string(path).ok()?;
-            serde_yaml::from_str(&s).map_err(|e| eprintln!("{e}")).ok()
+    pub fn new_yaml(w: i32, h: i32, label: &str, path: impl Into<PathBuf>) -> Result<Self, Error> {
+        fn load_fn(path: &Path) -> Result<Widget, Error> {
+            Format::Yaml.parse(&std::fs::read_to_string(path)?)
+        }
+        Self::new(w, h, label, path, load_fn)
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn new_toml(w: i32, h: i32, label: &str, path: impl Into<PathBuf>) -> Result<Self, Error> {
+        fn load_fn(path: &Path) -> Result<Widget, Error> {
+            Format::Toml.parse(&std::fs::read_to_string(path)?)
+        }
+        Self::new(w, h, label, path, load_fn)
+    }
+
+    #[cfg(feature = "sexp")]
+    pub fn new_sexp(w: i32, h: i32, label: &str, path: impl Into<PathBuf>) -> Result<Self, Error> {
+        fn load_fn(path: &Path) -> Result<Widget, Error> {
+            Format::Sexp.parse(&std::fs::read_to_string(path)?)
+        }
+        Self::new(w, h, label, path, load_fn)
+    }
+
+    #[cfg(feature = "ron")]
+    pub fn new_ron(w: i32, h: i32, label: &str, path: impl Into<PathBuf>) -> Result<Self, Error> {
+        fn load_fn(path: &Path) -> Result<Widget, Error> {
+            Format::Ron.parse(&std::fs::read_to_string(path)?)
+        }
+        Self::new(w, h, label, path, load_fn)
+    }
+
+    #[cfg(feature = "kdl")]
+    pub fn new_kdl(w: i32, h: i32, label: &str, path: impl Into<PathBuf>) -> Result<Self, Error> {
+        fn load_fn(path: &Path) -> Result<Widget, Error> {
+            Format::Kdl.parse(&std::fs::read_to_string(path)?)
         }
         Self::new(w, h, label, path, load_fn)
     }
@@ -143,10 +439,73 @@ impl DeclarativeApp {
             label: label.to_string(),
             path: None,
             widget,
-            load_fn: |_| None,
+            includes: vec![],
+            loader: Rc::new(|_: &Path| Err(Error::Io(std::io::ErrorKind::Unsupported.into()))),
+            registry: Registry::default(),
+            error_overlay: false,
+            keep_values: false,
+            strictness: Strictness::Permissive,
         }
     }
 
+    /// Register a named callback.
+    /// It gets attached to every widget whose `callback` property matches `name`, including after a reload
+    pub fn register<F: Fn(&mut widget::Widget) + 'static>(mut self, name: &str, cb: F) -> Self {
+        self.registry
+            .callbacks
+            .insert(name.to_string(), Rc::new(cb));
+        self
+    }
+
+    /// Register a named data source.
+    /// It provides the rows of every Table whose `rows` property matches `name`, and is called again on reloads
+    pub fn register_source<F: Fn() -> Vec<Vec<String>> + 'static>(
+        mut self,
+        name: &str,
+        source: F,
+    ) -> Self {
+        self.registry
+            .sources
+            .insert(name.to_string(), Rc::new(source));
+        self
+    }
+
+    /// Register a named highlighter.
+    /// It styles the text of every TextDisplay or TextEditor whose `highlight` property matches `name`,
+    /// and is called again whenever the text changes
+    pub fn register_highlighter<F: Fn(&str) -> String + 'static>(
+        mut self,
+        name: &str,
+        highlighter: F,
+    ) -> Self {
+        self.registry
+            .highlighters
+            .insert(name.to_string(), Rc::new(highlighter));
+        self
+    }
+
+    /// Show reload errors in a banner over the main window instead of only printing them.
+    /// The banner is dismissed on the next successful reload
+    pub fn error_overlay(mut self, flag: bool) -> Self {
+        self.error_overlay = flag;
+        self
+    }
+
+    /// Keep the values of widgets with an id across reloads, ex. the text entered in an Input,
+    /// instead of resetting them to the declared ones.
+    /// A value is still reset if its declaration changed
+    pub fn keep_values(mut self, flag: bool) -> Self {
+        self.keep_values = flag;
+        self
+    }
+
+    /// Set how problems in the gui description are handled, see [`validate`].
+    /// In strict mode, `run` fails on an invalid description, and invalid reloads are rejected
+    pub fn strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
     /// Run your declarative app.
     /// The callback exposes the app's main window
     pub fn run<F: FnMut(&mut window::Window) + 'static>(
@@ -154,11 +513,10 @@ impl DeclarativeApp {
         mut run_cb: F,
     ) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(path) = &self.path {
-            let mut win = window::Window::default()
-                .with_size(self.w, self.h)
-                .with_label(&self.label);
+            let mut win = self.main_window();
             if let Some(widget) = &self.widget {
-                utils::transform(widget);
+                validate::check(widget, self.strictness, &self.registry)?;
+                utils::transform(widget, &self.registry);
             }
             win.end();
             win.show();
@@ -167,25 +525,20 @@ impl DeclarativeApp {
                 frst.resize(0, 0, win.w(), win.h());
                 win.resizable(&frst);
             }
+            let mut windows = toplevel::Windows::default();
+            windows.update(
+                self.declared_windows(),
+                &self.registry,
+                self.w,
+                self.h,
+                &self.label,
+            );
 
             run_cb(&mut win);
 
             let flag = Arc::new(AtomicBool::new(false));
-            app::add_timeout3(0.1, {
-                let flag = flag.clone();
-                let mut win = win.clone();
-                move |_t| {
-                    if flag.load(Ordering::Relaxed) {
-                        run_cb(&mut win);
-                        flag.store(false, Ordering::Relaxed);
-                    }
-                    app::repeat_timeout3(0.1, _t);
-                }
-            });
-
-            let load_fn = self.load_fn;
             let mut watcher = notify::recommended_watcher({
-                let path = <&str>::clone(path);
+                let flag = flag.clone();
                 move |res: Result<Event, notify::Error>| match res {
                     Ok(event) => {
                         let mut needs_update = false;
@@ -201,24 +554,94 @@ impl DeclarativeApp {
                             _ => (),
                         }
                         if needs_update {
-                            if let Some(wid) = (load_fn)(path) {
+                            flag.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    Err(e) => eprintln!("{}", e),
+                }
+            })?;
+            watcher.watch(path, RecursiveMode::NonRecursive)?;
+            for inc in &self.includes {
+                watcher.watch(inc, RecursiveMode::NonRecursive)?;
+            }
+
+            app::add_timeout3(0.1, {
+                let mut win = win.clone();
+                let path = path.clone();
+                let mut watched = self.includes.clone();
+                let loader = self.loader.clone();
+                let registry = self.registry.clone();
+                let error_overlay = self.error_overlay;
+                let keep_values = self.keep_values;
+                let mut current = self.widget.clone();
+                let strictness = self.strictness;
+                let (w, h, label) = (self.w, self.h, self.label.clone());
+                let mut overlay = overlay::ErrorOverlay::default();
+                move |_t| {
+                    if flag.load(Ordering::Relaxed) {
+                        flag.store(false, Ordering::Relaxed);
+                        let mut includes = vec![];
+                        let res = include::load(&*loader, &path, &mut includes).and_then(|wid| {
+                            validate::check(&wid, strictness, &registry)?;
+                            Ok(wid)
+                        });
+                        // files newly included since the last load
+                        for inc in includes {
+                            if !watched.contains(&inc) {
+                                if let Err(e) = watcher.watch(&inc, RecursiveMode::NonRecursive) {
+                                    eprintln!("{}: {e}", inc.display());
+                                }
+                                watched.push(inc);
+                            }
+                        }
+                        match res {
+                            Ok(wid) => {
+                                // the window is only updated when its section changed
+                                let section = |w: Option<&Widget>| {
+                                    w.and_then(|w| w.window.clone()).unwrap_or_default()
+                                };
+                                let window = section(Some(&wid));
+                                if window != section(current.as_ref()) {
+                                    window.apply(&mut win, w, h, &label);
+                                }
+                                // `current` stays as declared, the kept values only go to what's built
+                                let built = match &current {
+                                    Some(current) if keep_values => {
+                                        utils::keep_values(current, &wid, utils::current_value)
+                                    }
+                                    _ => wid.clone(),
+                                };
+                                overlay.dismiss();
                                 win.clear();
                                 win.begin();
-                                utils::transform(&wid);
+                                utils::transform(&built, &registry);
                                 win.end();
                                 if let Some(mut frst) = win.child(0) {
                                     frst.resize(0, 0, win.w(), win.h());
                                     win.resizable(&frst);
                                 }
+                                windows.update(
+                                    built.windows.as_deref().unwrap_or_default(),
+                                    &registry,
+                                    w,
+                                    h,
+                                    &label,
+                                );
                                 app::redraw();
-                                flag.store(true, Ordering::Relaxed);
+                                run_cb(&mut win);
+                                current = Some(wid);
+                            }
+                            Err(e) => {
+                                eprintln!("{}: {e}", path.display());
+                                if error_overlay {
+                                    overlay.show(&mut win, &format!("{}: {e}", path.display()));
+                                }
                             }
                         }
                     }
-                    Err(e) => eprintln!("{}", e),
+                    app::repeat_timeout3(0.1, _t);
                 }
-            })?;
-            watcher.watch(&PathBuf::from(path), RecursiveMode::NonRecursive)?;
+            });
 
             self.a.run()?;
         } else {
@@ -232,11 +655,10 @@ impl DeclarativeApp {
         &self,
         mut run_cb: F,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut win = window::Window::default()
-            .with_size(self.w, self.h)
-            .with_label(&self.label);
+        let mut win = self.main_window();
         if let Some(widget) = &self.widget {
-            utils::transform(widget);
+            validate::check(widget, self.strictness, &self.registry)?;
+            utils::transform(widget, &self.registry);
         }
         win.end();
         win.show();
@@ -245,6 +667,13 @@ impl DeclarativeApp {
             frst.resize(0, 0, win.w(), win.h());
             win.resizable(&frst);
         }
+        toplevel::Windows::default().update(
+            self.declared_windows(),
+            &self.registry,
+            self.w,
+            self.h,
+            &self.label,
+        );
 
         run_cb(&mut win);
 
@@ -252,13 +681,31 @@ impl DeclarativeApp {
         Ok(())
     }
 
-    /// Just load the image of the window
-    pub fn dump_image(&self) {
+    /// The main window, sized and labeled using the app's arguments, unless the root widget's
+    /// `window` section overrides them
+    fn main_window(&self) -> window::Window {
         let mut win = window::Window::default()
             .with_size(self.w, self.h)
             .with_label(&self.label);
+        if let Some(section) = self.widget.as_ref().and_then(|w| w.window.as_ref()) {
+            section.apply(&mut win, self.w, self.h, &self.label);
+        }
+        win
+    }
+
+    /// The widgets of the root widget's `windows` section
+    fn declared_windows(&self) -> &[Widget] {
+        self.widget
+            .as_ref()
+            .and_then(|w| w.windows.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Just load the image of the window
+    pub fn dump_image(&self) {
+        let mut win = self.main_window();
         if let Some(widget) = &self.widget {
-            utils::transform(widget);
+            utils::transform(widget, &self.registry);
         }
         win.end();
         win.show();
@@ -0,0 +1,18 @@
+use crate::{Error, Widget};
+use std::path::Path;
+
+/// Loads the gui description, both initially and whenever the gui file changes.
+/// Implemented for closures and functions taking a path, so a loader can capture state
+pub trait Loader {
+    /// Load the widget tree from `path`
+    fn load(&self, path: &Path) -> Result<Widget, Error>;
+}
+
+impl<F> Loader for F
+where
+    F: Fn(&Path) -> Result<Widget, Error>,
+{
+    fn load(&self, path: &Path) -> Result<Widget, Error> {
+        self(path)
+    }
+}
@@ -0,0 +1,33 @@
+use fltk::{enums::*, prelude::*, *};
+
+/// A banner drawn over the main window, showing the error of the last failed reload
+#[derive(Debug, Default)]
+pub(crate) struct ErrorOverlay {
+    frame: Option<frame::Frame>,
+}
+
+impl ErrorOverlay {
+    /// Show `msg` over the window's current contents, replacing any previous error
+    pub fn show(&mut self, win: &mut window::Window, msg: &str) {
+        self.dismiss();
+        let h = (win.h() / 3).max(60).min(win.h());
+        win.begin();
+        let mut f = frame::Frame::new(0, 0, win.w(), h, None);
+        win.end();
+        f.set_frame(FrameType::BorderBox);
+        f.set_color(Color::from_hex(0xffdddd));
+        f.set_label_color(Color::from_hex(0x800000));
+        f.set_align(Align::Left | Align::Top | Align::Inside | Align::Wrap);
+        // '@' starts a symbol in fltk labels
+        f.set_label(&format!("Reload failed:\n{}", msg.replace('@', "@@")));
+        self.frame = Some(f);
+        win.redraw();
+    }
+
+    /// Remove the banner, if any
+    pub fn dismiss(&mut self) {
+        if let Some(f) = self.frame.take() {
+            app::delete_widget(f);
+        }
+    }
+}
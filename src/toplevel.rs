@@ -0,0 +1,140 @@
+use crate::{utils, Registry, Widget};
+use fltk::{prelude::*, *};
+use serde_derive::{Deserialize, Serialize};
+
+/// The `window` section of the root widget, describing the app's main window,
+/// or of an entry of its `windows` section, describing another top-level window.
+/// Unset fields fall back to the arguments passed to `DeclarativeApp`, or to fltk's defaults
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Toplevel {
+    /// The name used to show or hide the window, see [`crate::show_window`]
+    pub(crate) name: Option<String>,
+    #[serde(alias = "label")]
+    pub(crate) title: Option<String>,
+    pub(crate) x: Option<i32>,
+    pub(crate) y: Option<i32>,
+    pub(crate) w: Option<i32>,
+    pub(crate) h: Option<i32>,
+    pub(crate) minw: Option<i32>,
+    pub(crate) minh: Option<i32>,
+    pub(crate) maxw: Option<i32>,
+    pub(crate) maxh: Option<i32>,
+    /// The path of the window's icon
+    pub(crate) icon: Option<String>,
+    pub(crate) modal: Option<bool>,
+    pub(crate) border: Option<bool>,
+    pub(crate) fullscreen: Option<bool>,
+    /// The window class, used by X11 window managers. It can't be changed once the window is shown
+    pub(crate) xclass: Option<String>,
+    pub(crate) color: Option<String>,
+    /// Whether a window of the `windows` section is shown at startup, the main window always is
+    pub(crate) show: Option<bool>,
+}
+
+impl Toplevel {
+    /// Apply the section to a window, where `w`, `h` and `label` are the arguments passed to the app
+    pub(crate) fn apply(&self, win: &mut window::Window, w: i32, h: i32, label: &str) {
+        if let Some(name) = &self.name {
+            win.set_id(name);
+        }
+        win.set_label(self.title.as_deref().unwrap_or(label));
+        // leaving fullscreen restores the previous size, before the declared one is applied
+        let fullscreen = self.fullscreen.unwrap_or(false);
+        if fullscreen != win.fullscreen_active() {
+            win.fullscreen(fullscreen);
+        }
+        if !fullscreen {
+            win.resize(
+                self.x.unwrap_or(win.x()),
+                self.y.unwrap_or(win.y()),
+                self.w.unwrap_or(w),
+                self.h.unwrap_or(h),
+            );
+        }
+        // 0 means no limit
+        win.size_range(
+            self.minw.unwrap_or(0),
+            self.minh.unwrap_or(0),
+            self.maxw.unwrap_or(0),
+            self.maxh.unwrap_or(0),
+        );
+        match &self.icon {
+            Some(path) => match image::SharedImage::load(path).and_then(|i| i.to_rgb()) {
+                Ok(icon) => win.set_icon(Some(icon)),
+                Err(e) => eprintln!("Couldn't load the window icon {path}: {e}"),
+            },
+            None => win.set_icon(None::<image::RgbImage>),
+        }
+        win.make_modal(self.modal.unwrap_or(false));
+        win.set_border(self.border.unwrap_or(true));
+        if let Some(xclass) = &self.xclass {
+            if !win.shown() {
+                win.set_xclass(xclass);
+            }
+        }
+        let color = self
+            .color
+            .as_deref()
+            .and_then(|c| enums::Color::from_hex_str(c).ok());
+        win.set_color(color.unwrap_or(enums::Color::Background));
+    }
+}
+
+/// The windows of the root widget's `windows` section, along with their key and their section
+#[derive(Default)]
+pub(crate) struct Windows {
+    windows: Vec<(String, window::Window, Toplevel)>,
+}
+
+impl Windows {
+    /// Build the declared windows, where `w`, `h` and `label` are the arguments passed to the app.
+    /// Windows which already exist are matched by name, or by position if unnamed, and their content
+    /// is rebuilt. They stay shown or hidden. The windows which aren't declared anymore are deleted
+    pub(crate) fn update(
+        &mut self,
+        widgets: &[Widget],
+        registry: &Registry,
+        w: i32,
+        h: i32,
+        label: &str,
+    ) {
+        let mut windows = vec![];
+        for (i, widget) in widgets.iter().enumerate() {
+            let section = widget.window.clone().unwrap_or_default();
+            let key = section.name.clone().unwrap_or_else(|| format!("#{i}"));
+            let existing = self.windows.iter().position(|(k, _, _)| *k == key);
+            let (mut win, previous) = match existing {
+                Some(pos) => {
+                    let (_, win, previous) = self.windows.remove(pos);
+                    (win, Some(previous))
+                }
+                None => {
+                    let win = window::Window::default();
+                    win.end();
+                    (win, None)
+                }
+            };
+            if previous.as_ref() != Some(&section) {
+                section.apply(&mut win, w, h, label);
+            }
+            win.clear();
+            win.begin();
+            utils::transform(widget, registry);
+            win.end();
+            if let Some(mut first) = win.child(0) {
+                first.resize(0, 0, win.w(), win.h());
+                win.resizable(&first);
+            }
+            win.redraw();
+            if previous.is_none() && section.show == Some(true) {
+                win.show();
+            }
+            windows.push((key, win, section));
+        }
+        for (_, mut win, _) in self.windows.drain(..) {
+            win.hide();
+            window::Window::delete(win);
+        }
+        self.windows = windows;
+    }
+}
@@ -1,5 +1,9 @@
-use crate::Widget;
+use crate::{
+    items::{Item, Node, Rows, Size},
+    Callback, Registry, Widget, WidgetValue,
+};
 use fltk::{prelude::*, *};
+use std::{cell::Cell, collections::HashMap, rc::Rc};
 
 macro_rules! handle_text {
     ($w: ident, $widget: ident) => {
@@ -19,7 +23,421 @@ macro_rules! handle_text {
     };
 }
 
-pub(crate) fn handle_w<T>(w: &Widget, widget: &mut T)
+macro_rules! handle_valuator {
+    ($w: ident, $widget: ident) => {
+        if let Some(v) = $w.minimum {
+            $widget.set_minimum(v);
+        }
+        if let Some(v) = $w.maximum {
+            $widget.set_maximum(v);
+        }
+        if let Some(v) = $w.step {
+            $widget.set_step(v, 1);
+        }
+        if let Some(v) = $w.precision {
+            $widget.set_precision(v);
+        }
+        if let Some(v) = $w.value.as_ref().and_then(WidgetValue::as_f64) {
+            $widget.set_value($widget.clamp(v));
+        }
+    };
+}
+
+macro_rules! handle_menu {
+    ($w: ident, $widget: ident, $registry: ident) => {
+        if let Some(items) = &$w.items {
+            add_items(&mut $widget, "", items, $registry);
+        }
+    };
+}
+
+macro_rules! handle_browser {
+    ($w: ident, $widget: ident) => {
+        // 0 terminates the widths in fltk, hidden columns use -1
+        if let Some(widths) = &$w.column_widths {
+            let end = widths.iter().position(|&w| w == 0).unwrap_or(widths.len());
+            $widget.set_column_widths(&widths[..end]);
+        }
+        if let Some(c) = $w.column_char.as_deref().and_then(|s| s.chars().next()) {
+            $widget.set_column_char(c);
+        }
+        if let Some(items) = &$w.items {
+            for item in items {
+                $widget.add(&item.line());
+                let line = $widget.size();
+                if let Some(path) = &item.icon {
+                    $widget.set_icon(line, image::SharedImage::load(path).ok());
+                }
+                if item.selected == Some(true) {
+                    $widget.select(line);
+                }
+            }
+        }
+    };
+}
+
+macro_rules! handle_dial {
+    ($w: ident, $widget: ident) => {
+        if let Some([a1, a2]) = $w.angles.as_deref() {
+            $widget.set_angles(*a1, *a2);
+        }
+    };
+}
+
+/// `Fl_Counter::lstep` isn't exposed by fltk-rs, so the counter keeps handling its arrows,
+/// including their press and repeat, while the change made by the large step arrows, 1, is
+/// replaced by `lstep` before running the widget's callback
+fn handle_lstep(counter: &mut valuator::Counter, lstep: f64, cb: Option<Callback>) {
+    // the large step arrow being pressed, and the value before it last changed it
+    let pressed = Rc::new(Cell::new(None));
+    let before = Rc::new(Cell::new(counter.value()));
+    counter.handle({
+        let pressed = pressed.clone();
+        let before = before.clone();
+        move |c, ev| {
+            match ev {
+                enums::Event::Push | enums::Event::Drag => {
+                    let normal =
+                        c.get_type::<valuator::CounterType>() == valuator::CounterType::Normal;
+                    let arrow = if normal && app::event_inside_widget(c) {
+                        large_step_arrow(app::event_x() - c.x(), c.w())
+                    } else {
+                        None
+                    };
+                    if arrow != pressed.get() {
+                        before.set(c.value());
+                    }
+                    pressed.set(arrow);
+                }
+                enums::Event::Released | enums::Event::Unfocus => pressed.set(None),
+                _ => (),
+            }
+            false
+        }
+    });
+    counter.set_callback(move |c| {
+        if let Some(direction) = pressed.get() {
+            let v = c.clamp(c.round(before.get() + direction * lstep));
+            c.set_value(v);
+            before.set(v);
+        }
+        if let Some(cb) = &cb {
+            cb(&mut c.as_base_widget());
+        }
+    });
+}
+
+/// The direction of the large step arrow at `x`, relative to a normal counter of width `w`.
+/// Each of its 4 arrows is 15/100 of the width, like in `Fl_Counter::calc_mouseobj`
+fn large_step_arrow(x: i32, w: i32) -> Option<f64> {
+    let arrow = w * 15 / 100;
+    if (0..arrow).contains(&x) {
+        Some(-1.0)
+    } else if (w - arrow..w).contains(&x) {
+        Some(1.0)
+    } else {
+        None
+    }
+}
+
+/// Parse a shortcut, either as an integer or as keys joined by `+`, ex. `Ctrl+Shift+S` or `F5`
+pub(crate) fn parse_shortcut(s: &str) -> Option<enums::Shortcut> {
+    let s = s.trim();
+    if let Ok(v) = s.parse::<i32>() {
+        return Some(enums::Shortcut::from_i32(v));
+    }
+    let (modifiers, key) = match s.strip_suffix("++") {
+        Some(modifiers) => (modifiers, "+"),
+        None => s.rsplit_once('+').unwrap_or(("", s)),
+    };
+    let mut shortcut = enums::Shortcut::None;
+    for m in modifiers.split('+').filter(|m| !m.is_empty()) {
+        shortcut |= match m.trim().to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => enums::Shortcut::Ctrl,
+            "alt" => enums::Shortcut::Alt,
+            "shift" => enums::Shortcut::Shift,
+            "meta" | "super" => enums::Shortcut::Meta,
+            "cmd" | "command" => enums::Shortcut::Command,
+            _ => return None,
+        };
+    }
+    let key = key.trim();
+    let mut chars = key.chars();
+    let key = match (chars.next(), chars.next()) {
+        (Some(c), None) => enums::Shortcut::from_char(c.to_ascii_lowercase()),
+        _ => {
+            let key = key.to_ascii_lowercase();
+            let named = match key.as_str() {
+                "enter" | "return" => enums::Key::Enter,
+                "escape" | "esc" => enums::Key::Escape,
+                "tab" => enums::Key::Tab,
+                "backspace" => enums::Key::BackSpace,
+                "delete" | "del" => enums::Key::Delete,
+                "insert" => enums::Key::Insert,
+                "home" => enums::Key::Home,
+                "end" => enums::Key::End,
+                "pageup" => enums::Key::PageUp,
+                "pagedown" => enums::Key::PageDown,
+                "left" => enums::Key::Left,
+                "right" => enums::Key::Right,
+                "up" => enums::Key::Up,
+                "down" => enums::Key::Down,
+                "space" => enums::Key::from_char(' '),
+                _ => match key.strip_prefix('f').and_then(|n| n.parse::<i32>().ok()) {
+                    Some(n @ 1..=12) => enums::Key::from_i32(enums::Key::F1.bits() + n - 1),
+                    _ => return None,
+                },
+            };
+            enums::Shortcut::from_key(named)
+        }
+    };
+    Some(shortcut | key)
+}
+
+/// Add tree nodes, a node's label being a path relative to its parent
+fn add_nodes(tree: &mut tree::Tree, prefix: &str, nodes: &[Node]) {
+    for node in nodes {
+        let path = if prefix.is_empty() {
+            node.label.clone()
+        } else {
+            format!("{prefix}/{}", node.label)
+        };
+        let Some(mut item) = tree.add(&path) else {
+            continue;
+        };
+        if let Some(path) = &node.icon {
+            item.set_user_icon(image::SharedImage::load(path).ok());
+        }
+        if let Some(data) = &node.data {
+            item.set_user_data(data.clone());
+        }
+        if let Some(nodes) = &node.nodes {
+            add_nodes(tree, &path, nodes);
+        }
+        match node.open {
+            Some(true) => item.open(),
+            Some(false) => item.close(),
+            None => (),
+        }
+        if node.selected == Some(true) {
+            item.select(1);
+        }
+    }
+}
+
+/// Attach a buffer, seeded from `textfile` or `text`, and style it using the registered highlighter
+fn handle_display<T: DisplayExt>(w: &Widget, display: &mut T, registry: &Registry) {
+    let mut buf = text::TextBuffer::default();
+    if let Some(path) = &w.textfile {
+        if let Err(e) = buf.load_file(path) {
+            eprintln!("{path}: {e}");
+        }
+    } else if let Some(text) = &w.text {
+        buf.set_text(text);
+    }
+    display.set_buffer(buf.clone());
+    if let Some(mode) = &w.wrap {
+        let margin = w.wrapmargin.unwrap_or(0);
+        match mode.as_str() {
+            "None" => display.wrap_mode(text::WrapMode::None, margin),
+            "AtColumn" => display.wrap_mode(text::WrapMode::AtColumn, margin),
+            "AtPixel" => display.wrap_mode(text::WrapMode::AtPixel, margin),
+            "AtBounds" => display.wrap_mode(text::WrapMode::AtBounds, margin),
+            _ => (),
+        }
+    }
+    if let Some(width) = w.linenumbers {
+        display.set_linenumber_width(width);
+    }
+    let Some(name) = &w.highlight else {
+        return;
+    };
+    let Some(highlighter) = registry.highlighters.get(name).cloned() else {
+        eprintln!("Unknown highlighter: {name}");
+        return;
+    };
+    let styles: Vec<_> = w
+        .styles
+        .iter()
+        .flatten()
+        .map(|style| text::StyleTableEntry {
+            color: style
+                .color
+                .as_deref()
+                .and_then(|c| enums::Color::from_hex_str(c).ok())
+                .unwrap_or(display.text_color()),
+            font: style
+                .font
+                .map_or(display.text_font(), |f| enums::Font::by_index(f as usize)),
+            size: style.size.unwrap_or(display.text_size()),
+        })
+        .collect();
+    let mut style_buf = text::TextBuffer::default();
+    style_buf.set_text(&fit_styles(highlighter(&buf.text()), buf.length()));
+    display.set_highlight_data(style_buf.clone(), styles);
+    buf.add_modify_callback2(move |buf, _, _, _, _, _| {
+        style_buf.set_text(&fit_styles(highlighter(&buf.text()), buf.length()));
+    });
+}
+
+/// Make a highlighter's output as long as the text, since fltk reads a style for every byte of it.
+/// Missing styles are the first one, `A`, and extra ones are dropped
+fn fit_styles(styles: String, len: i32) -> String {
+    let len = len.max(0) as usize;
+    let mut styles: String = styles.chars().take(len).collect();
+    while styles.len() < len {
+        styles.push('A');
+    }
+    styles
+}
+
+/// Fill a table from its declared columns and rows, `selected` telling whether a cell is selected
+fn handle_table<T: TableExt + 'static>(
+    w: &Widget,
+    table: &mut T,
+    registry: &Registry,
+    selected: fn(&mut T, i32, i32) -> bool,
+) {
+    let columns = w.columns.clone().unwrap_or_default();
+    let rows = match (&w.rows, w.rows.as_ref().and_then(Rows::count)) {
+        // a number of empty rows
+        (_, Some(count)) => vec![vec![]; count.max(0) as usize],
+        (Some(Rows::Data(rows)), _) => rows
+            .iter()
+            .map(|row| row.iter().map(WidgetValue::to_text).collect())
+            .collect(),
+        (Some(Rows::Source(name)), _) => match registry.sources.get(name) {
+            Some(source) => source(),
+            None => {
+                eprintln!("Unknown data source: {name}");
+                vec![]
+            }
+        },
+        _ => vec![],
+    };
+    let cols = rows
+        .iter()
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0)
+        .max(columns.len());
+    table.set_rows(rows.len() as i32);
+    table.set_cols(cols as i32);
+    table.set_col_header(w.colheader.unwrap_or(!columns.is_empty()));
+    if let Some(v) = w.rowheader {
+        table.set_row_header(v);
+    }
+    if let Some(v) = w.rowresize {
+        table.set_row_resize(v);
+    }
+    if let Some(v) = w.colresize {
+        table.set_col_resize(v);
+    }
+    for (i, col) in columns.iter().enumerate() {
+        if let Some(width) = col.width {
+            table.set_col_width(i as i32, width);
+        }
+    }
+    table.draw_cell(move |t, ctx, row, col, x, y, w, h| match ctx {
+        table::TableContext::StartPage => draw::set_font(t.label_font(), t.label_size()),
+        table::TableContext::ColHeader => {
+            let label = columns.get(col as usize).map_or("", |c| c.label.as_str());
+            draw_header(label, x, y, w, h);
+        }
+        table::TableContext::RowHeader => draw_header(&(row + 1).to_string(), x, y, w, h),
+        table::TableContext::Cell => {
+            let text = rows
+                .get(row as usize)
+                .and_then(|r| r.get(col as usize))
+                .map_or("", String::as_str);
+            let align = columns
+                .get(col as usize)
+                .and_then(|c| c.align)
+                .map_or(enums::Align::Left, enums::Align::from_bits_retain);
+            draw::push_clip(x, y, w, h);
+            draw::set_draw_color(if selected(t, row, col) {
+                t.selection_color()
+            } else {
+                enums::Color::BackGround2
+            });
+            draw::draw_rectf(x, y, w, h);
+            draw::set_draw_color(enums::Color::Foreground);
+            draw::draw_text2(text, x + 2, y, w - 4, h, align);
+            draw::set_draw_color(enums::Color::Light2);
+            draw::draw_rect(x, y, w, h);
+            draw::pop_clip();
+        }
+        _ => (),
+    });
+}
+
+fn draw_header(label: &str, x: i32, y: i32, w: i32, h: i32) {
+    draw::push_clip(x, y, w, h);
+    draw::draw_box(
+        enums::FrameType::ThinUpBox,
+        x,
+        y,
+        w,
+        h,
+        enums::Color::FrameDefault,
+    );
+    draw::set_draw_color(enums::Color::Foreground);
+    draw::draw_text2(label, x, y, w, h, enums::Align::Center);
+    draw::pop_clip();
+}
+
+/// Add menu items, submenus being added under their parent's path
+fn add_items<M: MenuExt>(menu: &mut M, prefix: &str, items: &[Item], registry: &Registry) {
+    for item in items {
+        // slashes in a label would otherwise create submenus
+        let label = item.label.replace('\\', "\\\\").replace('/', "\\/");
+        let path = if prefix.is_empty() {
+            label
+        } else {
+            format!("{prefix}/{label}")
+        };
+        let shortcut = item
+            .shortcut
+            .as_deref()
+            .and_then(parse_shortcut)
+            .unwrap_or(enums::Shortcut::None);
+        let mut flags = menu::MenuFlag::Normal;
+        for (set, flag) in [
+            (item.toggle, menu::MenuFlag::Toggle),
+            (item.radio, menu::MenuFlag::Radio),
+            (item.divider, menu::MenuFlag::MenuDivider),
+            (item.inactive, menu::MenuFlag::Inactive),
+            (item.value, menu::MenuFlag::Value),
+            (item.items.as_ref().map(|_| true), menu::MenuFlag::Submenu),
+        ] {
+            if set == Some(true) {
+                flags |= flag;
+            }
+        }
+        match item
+            .callback
+            .as_ref()
+            .map(|name| (name, registry.callbacks.get(name)))
+        {
+            Some((_, Some(cb))) => {
+                let cb = cb.clone();
+                menu.add(&path, shortcut, flags, move |m| cb(&mut m.as_base_widget()));
+            }
+            unknown => {
+                if let Some((name, _)) = unknown {
+                    eprintln!("Unknown callback: {name}");
+                }
+                // without its own callback, an item triggers the menu's callback
+                menu.add(&path, shortcut, flags, |m| m.do_callback());
+            }
+        }
+        if let Some(items) = &item.items {
+            add_items(menu, &path, items, registry);
+        }
+    }
+}
+
+pub(crate) fn handle_w<T>(w: &Widget, widget: &mut T, registry: &Registry)
 where
     T: Clone + Send + Sync + WidgetExt + 'static,
 {
@@ -37,7 +455,8 @@ where
             w.h.unwrap_or(widget.h()),
         );
     }
-    if let Some(fixed) = w.fixed {
+    // percentages, weights and limits are resolved by the parent, see `size_flex`
+    if let Some(Size::Pixels(fixed)) = w.fixed {
         if let Some(parent) = widget.parent() {
             if let Some(mut flex) = group::Flex::from_dyn_widget(&parent) {
                 flex.fixed(widget, fixed);
@@ -76,8 +495,48 @@ where
         }
     }
     if let Some(children) = &w.children {
+        let flex = group::Flex::from_dyn_widget(widget);
+        let mut sized = vec![];
         for c in children {
-            transform(c);
+            let n = flex.as_ref().map_or(0, |f| f.children());
+            transform(c, registry);
+            if let Some(child) = flex.as_ref().and_then(|f| f.child(n)) {
+                sized.push((child, Sizing::of(c)));
+            }
+        }
+        if let Some(flex) = flex {
+            size_flex(flex, sized);
+        }
+    }
+    if w.row.is_some() || w.col.is_some() {
+        if let Some(parent) = widget.parent() {
+            if let Some(mut grid) = group::Grid::from_dyn_widget(&parent) {
+                let row = w.row.unwrap_or(0).max(0) as usize;
+                let col = w.col.unwrap_or(0).max(0) as usize;
+                let align = match w.alignment.as_deref() {
+                    Some("Center") => group::GridAlign::CENTER,
+                    Some("Top") => group::GridAlign::TOP,
+                    Some("Bottom") => group::GridAlign::BOTTOM,
+                    Some("Left") => group::GridAlign::LEFT,
+                    Some("Right") => group::GridAlign::RIGHT,
+                    Some("Horizontal") => group::GridAlign::HORIZONTAL,
+                    Some("Vertical") => group::GridAlign::VERTICAL,
+                    Some("Proportional") => group::GridAlign::PROPORTIONAL,
+                    Some("TopLeft") => group::GridAlign::TOP_LEFT,
+                    Some("TopRight") => group::GridAlign::TOP_RIGHT,
+                    Some("BottomLeft") => group::GridAlign::BOTTOM_LEFT,
+                    Some("BottomRight") => group::GridAlign::BOTTOM_RIGHT,
+                    _ => group::GridAlign::FILL,
+                };
+                // the grid keeps the child's size as the minimum size of its cell,
+                // so only a declared size is kept, once the child's own children are built
+                widget.resize(widget.x(), widget.y(), w.w.unwrap_or(0), w.h.unwrap_or(0));
+                let rows = row..row + w.rowspan.unwrap_or(1).max(1) as usize;
+                let cols = col..col + w.colspan.unwrap_or(1).max(1) as usize;
+                if let Err(e) = grid.set_widget_ext(widget, rows, cols, align) {
+                    eprintln!("Couldn't place {} in the grid: {e}", w.widget);
+                }
+            }
         }
     }
     if let Some(v) = w.hide {
@@ -104,6 +563,14 @@ where
             }
         }
     }
+    if let Some(name) = &w.callback {
+        if let Some(cb) = registry.callbacks.get(name) {
+            let cb = cb.clone();
+            widget.set_callback(move |w| cb(&mut w.as_base_widget()));
+        } else {
+            eprintln!("Unknown callback: {name}");
+        }
+    }
     if let Some(tip) = &w.tooltip {
         widget.set_tooltip(tip);
     }
@@ -138,25 +605,22 @@ where
                 b.set_down_frame(unsafe { enums::FrameType::from_i32(f as i32) });
             }
         }
-        if let Some(f) = &w.shortcut {
-            if let Ok(sh) = f.parse::<i32>() {
-                b.set_shortcut(unsafe { std::mem::transmute(sh) });
-            }
+        if let Some(sh) = w.shortcut.as_deref().and_then(parse_shortcut) {
+            b.set_shortcut(sh);
         }
-    }
-    if let Some(mut b) = valuator::Slider::from_dyn_widget(widget) {
-        if let Some(sz) = w.minimum {
-            b.set_minimum(sz);
+        if let Some(v) = &w.value {
+            b.set_value(v.as_bool());
         }
-        if let Some(sz) = w.maximum {
-            b.set_maximum(sz);
+    }
+    if let Some(mut i) = input::Input::from_dyn_widget(widget) {
+        if let Some(v) = &w.value {
+            i.set_value(&v.to_text());
         }
+    }
+    if let Some(mut b) = valuator::Slider::from_dyn_widget(widget) {
         if let Some(sz) = w.slidersize {
             b.set_slider_size(sz as _);
         }
-        if let Some(sz) = w.step {
-            b.set_step(sz, 1);
-        }
     }
     if let Some(gap) = w.pad {
         if let Some(mut b) = group::Flex::from_dyn_widget(widget) {
@@ -168,285 +632,849 @@ where
     }
 }
 
-pub(crate) fn transform(w: &Widget) {
+/// How a Flex's child is sized along the Flex's direction
+struct Sizing {
+    fixed: Option<Size>,
+    weight: Option<i32>,
+    min: Option<Size>,
+    max: Option<Size>,
+}
+
+impl Sizing {
+    fn of(w: &Widget) -> Self {
+        Sizing {
+            fixed: w.fixed,
+            weight: w.weight,
+            min: w.min,
+            max: w.max,
+        }
+    }
+}
+
+/// Size the children of a Flex whenever it's resized, if any of them uses a percentage, a weight or a limit.
+/// Otherwise the Flex lays them out itself
+fn size_flex(mut flex: group::Flex, children: Vec<(widget::Widget, Sizing)>) {
+    let dynamic = children.iter().any(|(_, c)| {
+        c.weight.is_some()
+            || c.min.is_some()
+            || c.max.is_some()
+            || matches!(c.fixed, Some(Size::Percent(_)))
+    });
+    if !dynamic {
+        return;
+    }
+    let resize = move |flex: &mut group::Flex, _x: i32, _y: i32, w: i32, h: i32| {
+        let visible: Vec<_> = children.iter().filter(|(c, _)| c.visible()).collect();
+        let (left, top, right, bottom) = flex.margins();
+        let gaps = flex.pad() * (visible.len() as i32 - 1).max(0);
+        let available = if flex.get_type::<group::FlexType>() == group::FlexType::Row {
+            w - left - right - gaps
+        } else {
+            h - top - bottom - gaps
+        };
+        let sizes = flex_sizes(available.max(0), visible.iter().map(|(_, c)| c));
+        for ((child, _), size) in visible.iter().zip(sizes) {
+            // a size of 0 would make the child flexible again
+            flex.fixed(child, size.max(1));
+        }
+    };
+    let (x, y, w, h) = (flex.x(), flex.y(), flex.w(), flex.h());
+    resize(&mut flex, x, y, w, h);
+    flex.resize_callback(resize);
+}
+
+/// The sizes of a Flex's children sharing the `available` space: fixed sizes and percentages come first,
+/// and the rest is shared according to the weights, 1 by default, until no child exceeds its limits
+fn flex_sizes<'a>(available: i32, children: impl Iterator<Item = &'a Sizing>) -> Vec<i32> {
+    let children: Vec<_> = children.collect();
+    let clamp = |c: &Sizing, size: i32| {
+        let min = c.min.map(|m| m.resolve(available));
+        let max = c.max.map(|m| m.resolve(available));
+        let size = max.map_or(size, |max| size.min(max));
+        min.map_or(size, |min| size.max(min)).max(0)
+    };
+    let mut sizes: Vec<_> = children
+        .iter()
+        .map(|c| c.fixed.map(|f| clamp(c, f.resolve(available))))
+        .collect();
+    loop {
+        let rest = available - sizes.iter().flatten().sum::<i32>();
+        let weight = |c: &Sizing| c.weight.unwrap_or(1).max(0);
+        let flexible: Vec<_> = (0..children.len())
+            .filter(|&i| sizes[i].is_none())
+            .collect();
+        let total: i32 = flexible.iter().map(|&i| weight(children[i])).sum();
+        let share = |i: usize| {
+            if total == 0 {
+                0
+            } else {
+                (rest.max(0) as i64 * weight(children[i]) as i64 / total as i64) as i32
+            }
+        };
+        // the children whose share exceeds their limits get their limit, and the rest is shared again
+        let limited: Vec<_> = flexible
+            .iter()
+            .filter(|&&i| clamp(children[i], share(i)) != share(i))
+            .copied()
+            .collect();
+        if limited.is_empty() {
+            for &i in &flexible {
+                sizes[i] = Some(share(i));
+            }
+            // the pixels lost to rounding go to the last child sharing the space
+            if let Some(&last) = flexible.iter().rev().find(|&&i| weight(children[i]) > 0) {
+                let left = available - sizes.iter().flatten().sum::<i32>();
+                sizes[last] = sizes[last].map(|s| clamp(children[last], s + left.max(0)));
+            }
+            return sizes.into_iter().flatten().collect();
+        }
+        for i in limited {
+            sizes[i] = Some(clamp(children[i], share(i)));
+        }
+    }
+}
+
+pub(crate) fn transform(w: &Widget, registry: &Registry) {
     match w.widget.as_str() {
         "Column" => {
             let mut c = group::Flex::default_fill().column();
-            handle_w(w, &mut c);
+            handle_w(w, &mut c, registry);
         }
         "Row" => {
             let mut c = group::Flex::default_fill().row();
-            handle_w(w, &mut c);
+            handle_w(w, &mut c, registry);
         }
         "Button" => {
             let mut b = button::Button::default_fill();
-            handle_w(w, &mut b);
+            handle_w(w, &mut b, registry);
         }
         "CheckButton" => {
             let mut b = button::CheckButton::default_fill();
-            handle_w(w, &mut b);
+            handle_w(w, &mut b, registry);
         }
         "RadioButton" => {
             let mut b = button::RadioButton::default_fill();
-            handle_w(w, &mut b);
+            handle_w(w, &mut b, registry);
         }
         "ToggleButton" => {
             let mut b = button::ToggleButton::default_fill();
-            handle_w(w, &mut b);
+            handle_w(w, &mut b, registry);
         }
         "RadioRoundButton" => {
             let mut b = button::RadioRoundButton::default_fill();
-            handle_w(w, &mut b);
+            handle_w(w, &mut b, registry);
         }
         "ReturnButton" => {
             let mut b = button::ReturnButton::default_fill();
-            handle_w(w, &mut b);
+            handle_w(w, &mut b, registry);
         }
         "Frame" => {
             let mut f = frame::Frame::default_fill();
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "Group" => {
             let mut f = group::Group::default_fill();
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "Pack" => {
             let mut f = group::Pack::default_fill();
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "Tile" => {
             let mut f = group::Tile::default_fill();
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
+        }
+        "Grid" => {
+            let mut f = group::Grid::default_fill();
+            let rows = w.rows.as_ref().and_then(Rows::count).unwrap_or(1);
+            f.set_layout(rows, w.cols.unwrap_or(1));
+            f.set_gap(w.rowgap.unwrap_or(0), w.colgap.unwrap_or(0));
+            for (i, weight) in w.rowweights.iter().flatten().enumerate() {
+                f.set_row_weight(i as i32, *weight);
+            }
+            for (i, weight) in w.colweights.iter().flatten().enumerate() {
+                f.set_col_weight(i as i32, *weight);
+            }
+            handle_w(w, &mut f, registry);
         }
         "Tabs" => {
             let mut f = group::Tabs::default_fill();
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "Scroll" => {
             let mut f = group::Scroll::default_fill();
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "ColorChooser" => {
             let mut f = group::ColorChooser::default_fill();
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "TextDisplay" => {
             let mut f = text::TextDisplay::default_fill();
             handle_text!(w, f);
-            let buf = text::TextBuffer::default();
-            f.set_buffer(buf);
-            handle_w(w, &mut f);
+            handle_display(w, &mut f, registry);
+            handle_w(w, &mut f, registry);
         }
         "TextEditor" => {
             let mut f = text::TextEditor::default_fill();
             handle_text!(w, f);
-            let buf = text::TextBuffer::default();
-            f.set_buffer(buf);
-            handle_w(w, &mut f);
+            handle_display(w, &mut f, registry);
+            handle_w(w, &mut f, registry);
         }
         "Input" => {
             let mut f = input::Input::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "IntInput" => {
             let mut f = input::IntInput::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "FloatInput" => {
             let mut f = input::FloatInput::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "SecretInput" => {
             let mut f = input::SecretInput::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "FileInput" => {
             let mut f = input::FileInput::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "MultilineInput" => {
             let mut f = input::MultilineInput::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "Output" => {
             let mut f = output::Output::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "MultilineOutput" => {
-            let mut f = output::Output::default_fill();
+            let mut f = output::MultilineOutput::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "MenuBar" => {
             let mut f = menu::MenuBar::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_menu!(w, f, registry);
+            handle_w(w, &mut f, registry);
         }
         "SysMenuBar" => {
             let mut f = menu::SysMenuBar::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_menu!(w, f, registry);
+            handle_w(w, &mut f, registry);
         }
         "Choice" => {
             let mut f = menu::Choice::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_menu!(w, f, registry);
+            if let Some(v) = w.value.as_ref().and_then(WidgetValue::as_f64) {
+                f.set_value(v as i32);
+            }
+            handle_w(w, &mut f, registry);
         }
         "Slider" => {
             let mut f = valuator::Slider::default_fill();
-            handle_w(w, &mut f);
+            handle_valuator!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "NiceSlider" => {
             let mut f = valuator::NiceSlider::default_fill();
-            handle_w(w, &mut f);
+            handle_valuator!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "FillSlider" => {
             let mut f = valuator::FillSlider::default_fill();
-            handle_w(w, &mut f);
+            handle_valuator!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "ValueSlider" => {
             let mut f = valuator::ValueSlider::default_fill();
+            handle_valuator!(w, f);
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "Dial" => {
             let mut f = valuator::Dial::default_fill();
-            handle_w(w, &mut f);
+            handle_valuator!(w, f);
+            handle_dial!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "LineDial" => {
             let mut f = valuator::LineDial::default_fill();
-            handle_w(w, &mut f);
+            handle_valuator!(w, f);
+            handle_dial!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "FillDial" => {
             let mut f = valuator::FillDial::default_fill();
-            handle_w(w, &mut f);
+            handle_valuator!(w, f);
+            handle_dial!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "Counter" => {
             let mut f = valuator::Counter::default_fill();
-            handle_w(w, &mut f);
+            handle_valuator!(w, f);
+            handle_w(w, &mut f, registry);
+            // wraps the callback set by handle_w
+            if let Some(lstep) = w.lstep {
+                let cb = w
+                    .callback
+                    .as_ref()
+                    .and_then(|name| registry.callbacks.get(name));
+                handle_lstep(&mut f, lstep, cb.cloned());
+            }
         }
         "Scrollbar" => {
             let mut f = valuator::Scrollbar::default_fill();
-            handle_w(w, &mut f);
+            handle_valuator!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "Roller" => {
             let mut f = valuator::Roller::default_fill();
-            handle_w(w, &mut f);
+            handle_valuator!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "Adjuster" => {
             let mut f = valuator::Adjuster::default_fill();
-            handle_w(w, &mut f);
+            handle_valuator!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "ValueInput" => {
             let mut f = valuator::ValueInput::default_fill();
+            handle_valuator!(w, f);
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "ValueOutput" => {
             let mut f = valuator::ValueOutput::default_fill();
+            handle_valuator!(w, f);
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "HorSlider" => {
             let mut f = valuator::HorSlider::default_fill();
-            handle_w(w, &mut f);
+            handle_valuator!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "HorNiceSlider" => {
             let mut f = valuator::HorNiceSlider::default_fill();
-            handle_w(w, &mut f);
+            handle_valuator!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "HorFillSlider" => {
             let mut f = valuator::HorFillSlider::default_fill();
-            handle_w(w, &mut f);
+            handle_valuator!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "HorValueSlider" => {
             let mut f = valuator::HorValueSlider::default_fill();
+            handle_valuator!(w, f);
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "Browser" => {
             let mut f = browser::Browser::default_fill();
-            handle_w(w, &mut f);
+            handle_browser!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "SelectBrowser" => {
             let mut f = browser::SelectBrowser::default_fill();
-            handle_w(w, &mut f);
+            handle_browser!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "HoldBrowser" => {
             let mut f = browser::HoldBrowser::default_fill();
-            handle_w(w, &mut f);
+            handle_browser!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "FileBrowser" => {
             let mut f = browser::FileBrowser::default_fill();
-            handle_w(w, &mut f);
+            handle_browser!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "CheckBrowser" => {
             let mut f = browser::CheckBrowser::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            if let Some(items) = &w.items {
+                for item in items {
+                    f.add(&item.label, item.value == Some(true));
+                }
+            }
+            handle_w(w, &mut f, registry);
         }
         "MultiBrowser" => {
             let mut f = browser::MultiBrowser::default_fill();
-            handle_w(w, &mut f);
+            handle_browser!(w, f);
+            handle_w(w, &mut f, registry);
         }
         "Table" => {
             let mut f = table::Table::default_fill();
-            handle_w(w, &mut f);
+            handle_table(w, &mut f, registry, |t, row, col| t.is_selected(row, col));
+            handle_w(w, &mut f, registry);
         }
         "TableRow" => {
             let mut f = table::TableRow::default_fill();
-            handle_w(w, &mut f);
+            if let Some(mode) = &w.selectmode {
+                match mode.as_str() {
+                    "None" => f.set_type(table::TableRowSelectMode::None),
+                    "Single" => f.set_type(table::TableRowSelectMode::Single),
+                    "Multi" => f.set_type(table::TableRowSelectMode::Multi),
+                    _ => (),
+                }
+            }
+            handle_table(w, &mut f, registry, |t, row, _| t.row_selected(row));
+            handle_w(w, &mut f, registry);
         }
         "Tree" => {
             let mut f = tree::Tree::default_fill();
-            handle_w(w, &mut f);
+            if let Some(v) = w.showroot {
+                f.set_show_root(v);
+            }
+            if let Some(style) = &w.connectorstyle {
+                match style.as_str() {
+                    "None" => f.set_connector_style(tree::TreeConnectorStyle::None),
+                    "Dotted" => f.set_connector_style(tree::TreeConnectorStyle::Dotted),
+                    "Solid" => f.set_connector_style(tree::TreeConnectorStyle::Solid),
+                    _ => (),
+                }
+            }
+            if let Some(mode) = &w.selectmode {
+                match mode.as_str() {
+                    "None" => f.set_select_mode(tree::TreeSelect::None),
+                    "Single" => f.set_select_mode(tree::TreeSelect::Single),
+                    "Multi" => f.set_select_mode(tree::TreeSelect::Multi),
+                    "SingleDraggable" => f.set_select_mode(tree::TreeSelect::SingleDraggable),
+                    _ => (),
+                }
+            }
+            if let Some(nodes) = &w.nodes {
+                add_nodes(&mut f, "", nodes);
+            }
+            handle_w(w, &mut f, registry);
         }
         "Spinner" => {
             let mut f = misc::Spinner::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            if let Some(v) = w.minimum {
+                f.set_minimum(v);
+            }
+            if let Some(v) = w.maximum {
+                f.set_maximum(v);
+            }
+            if let Some(v) = w.step {
+                f.set_step(v);
+            }
+            if let Some(v) = w.value.as_ref().and_then(WidgetValue::as_f64) {
+                f.set_value(v);
+            }
+            handle_w(w, &mut f, registry);
         }
         "Chart" => {
             let mut f = misc::Chart::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            if let Some(ty) = &w.charttype {
+                match ty.as_str() {
+                    "Bar" => f.set_type(misc::ChartType::Bar),
+                    "HorizontalBar" => f.set_type(misc::ChartType::HorizontalBar),
+                    "Line" => f.set_type(misc::ChartType::Line),
+                    "Fill" => f.set_type(misc::ChartType::Fill),
+                    "Spike" => f.set_type(misc::ChartType::Spike),
+                    "Pie" => f.set_type(misc::ChartType::Pie),
+                    "SpecialPie" => f.set_type(misc::ChartType::SpecialPie),
+                    _ => (),
+                }
+            }
+            if let Some([min, max]) = w.bounds.as_deref() {
+                f.set_bounds(*min, *max);
+            }
+            if let Some(v) = w.autosize {
+                f.make_autosize(v);
+            }
+            if let Some(v) = w.maxsize {
+                f.set_maximum_size(v);
+            }
+            for (i, entry) in w.entries.iter().flatten().enumerate() {
+                // without a color, the entries go through fltk's red, green, yellow, blue, magenta and cyan
+                let color = entry
+                    .color
+                    .as_deref()
+                    .and_then(|c| enums::Color::from_hex_str(c).ok())
+                    .unwrap_or_else(|| enums::Color::by_index(1 + (i % 6) as u8));
+                f.add(entry.value, &entry.label, color);
+            }
+            handle_w(w, &mut f, registry);
         }
         "Progress" => {
             let mut f = misc::Progress::default_fill();
-            handle_w(w, &mut f);
+            if let Some(v) = w.minimum {
+                f.set_minimum(v);
+            }
+            if let Some(v) = w.maximum {
+                f.set_maximum(v);
+            }
+            if let Some(v) = w.value.as_ref().and_then(WidgetValue::as_f64) {
+                f.set_value(v);
+            }
+            handle_w(w, &mut f, registry);
         }
         "InputChoice" => {
             let mut f = misc::InputChoice::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            if let Some(items) = &w.items {
+                add_items(&mut f.menu_button(), "", items, registry);
+            }
+            match &w.value {
+                Some(WidgetValue::Number(v)) => f.set_value_index(*v as i32),
+                Some(v) => f.set_value(&v.to_text()),
+                None => (),
+            }
+            handle_w(w, &mut f, registry);
         }
         "HelpView" => {
             let mut f = misc::HelpView::default_fill();
             handle_text!(w, f);
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "Window" => {
             let mut f = window::Window::default_fill();
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "MenuWindow" => {
             let mut f = window::MenuWindow::default_fill();
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         "GlutWindow" => {
             #[cfg(feature = "enable-glwindow")]
             let mut f = window::GlutWindow::default_fill();
             #[cfg(not(feature = "enable-glwindow"))]
             let mut f = window::Window::default_fill();
-            handle_w(w, &mut f);
+            handle_w(w, &mut f, registry);
         }
         _ => (),
     };
 }
+
+/// A value kept across reloads, see `DeclarativeApp::keep_values`
+pub(crate) struct KeptValue {
+    widget: String,
+    declared: Option<WidgetValue>,
+    current: WidgetValue,
+}
+
+fn number<T: ValuatorExt + Clone + 'static>(id: &str) -> Option<WidgetValue> {
+    app::widget_from_id::<T>(id).map(|v| WidgetValue::Number(v.value()))
+}
+
+fn checked<T: ButtonExt + Clone + 'static>(id: &str) -> Option<WidgetValue> {
+    app::widget_from_id::<T>(id).map(|b| WidgetValue::Bool(b.value()))
+}
+
+fn text<T: InputExt + Clone + 'static>(id: &str) -> Option<WidgetValue> {
+    app::widget_from_id::<T>(id).map(|i| WidgetValue::Text(i.value()))
+}
+
+/// The current value of a widget built from `w`, if it has an id
+pub(crate) fn current_value(w: &Widget) -> Option<WidgetValue> {
+    let id = w.id.as_deref()?;
+    match w.widget.as_str() {
+        "Button" => checked::<button::Button>(id),
+        "CheckButton" => checked::<button::CheckButton>(id),
+        "RadioButton" => checked::<button::RadioButton>(id),
+        "ToggleButton" => checked::<button::ToggleButton>(id),
+        "RadioRoundButton" => checked::<button::RadioRoundButton>(id),
+        "ReturnButton" => checked::<button::ReturnButton>(id),
+        "Input" => text::<input::Input>(id),
+        "IntInput" => text::<input::IntInput>(id),
+        "FloatInput" => text::<input::FloatInput>(id),
+        "SecretInput" => text::<input::SecretInput>(id),
+        "FileInput" => text::<input::FileInput>(id),
+        "MultilineInput" => text::<input::MultilineInput>(id),
+        "Output" => text::<output::Output>(id),
+        "MultilineOutput" => text::<output::MultilineOutput>(id),
+        "Choice" => {
+            app::widget_from_id::<menu::Choice>(id).map(|c| WidgetValue::Number(c.value() as f64))
+        }
+        "Slider" => number::<valuator::Slider>(id),
+        "NiceSlider" => number::<valuator::NiceSlider>(id),
+        "FillSlider" => number::<valuator::FillSlider>(id),
+        "ValueSlider" => number::<valuator::ValueSlider>(id),
+        "Dial" => number::<valuator::Dial>(id),
+        "LineDial" => number::<valuator::LineDial>(id),
+        "FillDial" => number::<valuator::FillDial>(id),
+        "Counter" => number::<valuator::Counter>(id),
+        "Scrollbar" => number::<valuator::Scrollbar>(id),
+        "Roller" => number::<valuator::Roller>(id),
+        "Adjuster" => number::<valuator::Adjuster>(id),
+        "ValueInput" => number::<valuator::ValueInput>(id),
+        "ValueOutput" => number::<valuator::ValueOutput>(id),
+        "HorSlider" => number::<valuator::HorSlider>(id),
+        "HorNiceSlider" => number::<valuator::HorNiceSlider>(id),
+        "HorFillSlider" => number::<valuator::HorFillSlider>(id),
+        "HorValueSlider" => number::<valuator::HorValueSlider>(id),
+        "Spinner" => {
+            app::widget_from_id::<misc::Spinner>(id).map(|s| WidgetValue::Number(s.value()))
+        }
+        "Progress" => {
+            app::widget_from_id::<misc::Progress>(id).map(|p| WidgetValue::Number(p.value()))
+        }
+        "InputChoice" => app::widget_from_id::<misc::InputChoice>(id)
+            .and_then(|c| c.value())
+            .map(WidgetValue::Text),
+        _ => None,
+    }
+}
+
+/// The description to build on reload, with the current values of the widgets built from `current`,
+/// as read by `read`, see `DeclarativeApp::keep_values`.
+/// `current` and `reloaded` are left as declared, so that a value is only reset if its declaration changed
+pub(crate) fn keep_values(
+    current: &Widget,
+    reloaded: &Widget,
+    read: impl Fn(&Widget) -> Option<WidgetValue>,
+) -> Widget {
+    let mut values = HashMap::new();
+    capture(current, &mut values, &read);
+    let mut built = reloaded.clone();
+    restore(&mut built, &values);
+    built
+}
+
+/// Collect the current values of the widgets built from `w`
+fn capture(
+    w: &Widget,
+    values: &mut HashMap<String, KeptValue>,
+    read: &impl Fn(&Widget) -> Option<WidgetValue>,
+) {
+    if let (Some(id), Some(current)) = (&w.id, read(w)) {
+        values.insert(
+            id.clone(),
+            KeptValue {
+                widget: w.widget.clone(),
+                declared: w.value.clone(),
+                current,
+            },
+        );
+    }
+    for c in w.children.iter().chain(&w.windows).flatten() {
+        capture(c, values, read);
+    }
+}
+
+/// Declare the kept values in `w`, unless the widget type or the declared value changed
+fn restore(w: &mut Widget, values: &HashMap<String, KeptValue>) {
+    if let Some(kept) = w.id.as_ref().and_then(|id| values.get(id)) {
+        if kept.widget == w.widget && kept.declared == w.value {
+            w.value = Some(kept.current.clone());
+        }
+    }
+    for c in w.children.iter_mut().chain(&mut w.windows).flatten() {
+        restore(c, values);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn widget(s: &str) -> Widget {
+        serde_json5::from_str(s).unwrap()
+    }
+
+    fn input_value(w: &Widget) -> Option<WidgetValue> {
+        w.children.as_ref().unwrap()[0].value.clone()
+    }
+
+    fn typed(text: &'static str) -> impl Fn(&Widget) -> Option<WidgetValue> {
+        move |w| (w.id.as_deref() == Some("name")).then(|| WidgetValue::Text(text.into()))
+    }
+
+    #[test]
+    fn values_are_kept_across_reloads() {
+        let declared =
+            widget(r#"{widget: "Column", children: [{widget: "Input", id: "name", value: "a"}]}"#);
+        // the user typed "b", then the file is saved unchanged
+        let current = declared.clone();
+        let built = keep_values(&current, &declared, typed("b"));
+        assert_eq!(input_value(&built), Some(WidgetValue::Text("b".into())));
+        assert_eq!(input_value(&declared), Some(WidgetValue::Text("a".into())));
+        // the user typed "c", then the file is saved unchanged again
+        let current = declared.clone();
+        let built = keep_values(&current, &declared, typed("c"));
+        assert_eq!(input_value(&built), Some(WidgetValue::Text("c".into())));
+        // a changed declaration resets the value
+        let changed =
+            widget(r#"{widget: "Column", children: [{widget: "Input", id: "name", value: "z"}]}"#);
+        let built = keep_values(&current, &changed, typed("d"));
+        assert_eq!(input_value(&built), Some(WidgetValue::Text("z".into())));
+    }
+
+    fn sizing(
+        fixed: Option<Size>,
+        weight: Option<i32>,
+        min: Option<i32>,
+        max: Option<i32>,
+    ) -> Sizing {
+        Sizing {
+            fixed,
+            weight,
+            min: min.map(Size::Pixels),
+            max: max.map(Size::Pixels),
+        }
+    }
+
+    fn flexible() -> Sizing {
+        sizing(None, None, None, None)
+    }
+
+    #[test]
+    fn flex_space_is_shared() {
+        let sizes = |available, children: &[Sizing]| flex_sizes(available, children.iter());
+        // evenly, with the pixels lost to rounding going to the last child
+        assert_eq!(
+            sizes(300, &[flexible(), flexible(), flexible()]),
+            [100, 100, 100]
+        );
+        assert_eq!(
+            sizes(100, &[flexible(), flexible(), flexible()]),
+            [33, 33, 34]
+        );
+        // fixed sizes and percentages come first
+        assert_eq!(
+            sizes(
+                400,
+                &[
+                    sizing(Some(Size::Pixels(100)), None, None, None),
+                    flexible()
+                ]
+            ),
+            [100, 300]
+        );
+        assert_eq!(
+            sizes(
+                400,
+                &[
+                    sizing(Some(Size::Percent(25.0)), None, None, None),
+                    flexible()
+                ]
+            ),
+            [100, 300]
+        );
+        // then weights
+        assert_eq!(
+            sizes(
+                250,
+                &[
+                    sizing(Some(Size::Pixels(50)), None, None, None),
+                    sizing(None, Some(1), None, None),
+                    sizing(None, Some(3), None, None),
+                ]
+            ),
+            [50, 50, 150]
+        );
+        assert_eq!(
+            sizes(200, &[sizing(None, Some(0), None, None), flexible()]),
+            [0, 200]
+        );
+        // limited children get their limit, and the rest is shared again
+        assert_eq!(
+            sizes(300, &[sizing(None, None, None, Some(50)), flexible()]),
+            [50, 250]
+        );
+        assert_eq!(
+            sizes(
+                300,
+                &[sizing(None, None, Some(200), None), flexible(), flexible()]
+            ),
+            [200, 50, 50]
+        );
+        // fixed sizes are limited too
+        assert_eq!(
+            sizes(
+                300,
+                &[
+                    sizing(Some(Size::Pixels(100)), None, None, Some(80)),
+                    flexible()
+                ]
+            ),
+            [80, 220]
+        );
+        // no space left
+        assert_eq!(
+            sizes(
+                300,
+                &[
+                    sizing(Some(Size::Pixels(400)), None, None, None),
+                    flexible()
+                ]
+            ),
+            [400, 0]
+        );
+    }
+
+    #[test]
+    fn shortcuts_are_parsed() {
+        use enums::{Key, Shortcut};
+        assert_eq!(parse_shortcut("Ctrl+O"), Some(Shortcut::Ctrl | 'o'));
+        assert_eq!(
+            parse_shortcut("ctrl + shift + s"),
+            Some(Shortcut::Ctrl | Shortcut::Shift | 's')
+        );
+        assert_eq!(parse_shortcut("Alt+F4"), Some(Shortcut::Alt | Key::F4));
+        assert_eq!(parse_shortcut("Ctrl++"), Some(Shortcut::Ctrl | '+'));
+        assert_eq!(
+            parse_shortcut("Cmd+Enter"),
+            Some(Shortcut::Command | Key::Enter)
+        );
+        assert_eq!(parse_shortcut("Delete"), Some(Shortcut::None | Key::Delete));
+        assert_eq!(parse_shortcut("F12"), Some(Shortcut::None | Key::F12));
+        assert_eq!(parse_shortcut("q"), Some(Shortcut::None | 'q'));
+        assert_eq!(parse_shortcut("262255"), Some(Shortcut::from_i32(262255)));
+        assert_eq!(parse_shortcut("Hyper+O"), None);
+        assert_eq!(parse_shortcut("Ctrl+F13"), None);
+        assert_eq!(parse_shortcut("Ctrl+Oops"), None);
+    }
+
+    #[test]
+    fn styles_fit_the_text() {
+        assert_eq!(fit_styles("AB".into(), 4), "ABAA");
+        assert_eq!(fit_styles("ABCD".into(), 2), "AB");
+        assert_eq!(fit_styles("ABC".into(), 3), "ABC");
+        assert_eq!(fit_styles("".into(), 0), "");
+    }
+
+    #[test]
+    fn large_step_arrows_are_the_outer_ones() {
+        // the arrows of a 100 pixels wide counter: 0..15, 15..30, 70..85 and 85..100
+        let arrows: Vec<_> = [0, 14, 15, 29, 50, 70, 84, 85, 99, 100]
+            .into_iter()
+            .map(|x| large_step_arrow(x, 100))
+            .collect();
+        assert_eq!(
+            arrows,
+            [
+                Some(-1.0),
+                Some(-1.0),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(1.0),
+                Some(1.0),
+                None
+            ]
+        );
+        assert_eq!(large_step_arrow(59, 400), Some(-1.0));
+        assert_eq!(large_step_arrow(60, 400), None);
+    }
+}
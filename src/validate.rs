@@ -0,0 +1,688 @@
+use crate::{items::Rows, Error, Registry, Widget};
+use fltk::enums;
+use std::{collections::HashSet, fmt};
+
+const FLEXES: &[&str] = &["Column", "Row"];
+
+const GROUPS: &[&str] = &[
+    "Column",
+    "Row",
+    "Group",
+    "Pack",
+    "Tile",
+    "Grid",
+    "Tabs",
+    "Scroll",
+    "Window",
+    "MenuWindow",
+    "GlutWindow",
+];
+
+const BUTTONS: &[&str] = &[
+    "Button",
+    "CheckButton",
+    "RadioButton",
+    "ToggleButton",
+    "RadioRoundButton",
+    "ReturnButton",
+];
+
+const SLIDERS: &[&str] = &[
+    "Slider",
+    "NiceSlider",
+    "FillSlider",
+    "ValueSlider",
+    "Scrollbar",
+    "HorSlider",
+    "HorNiceSlider",
+    "HorFillSlider",
+    "HorValueSlider",
+];
+
+const VALUATORS: &[&str] = &[
+    "Slider",
+    "NiceSlider",
+    "FillSlider",
+    "ValueSlider",
+    "Dial",
+    "LineDial",
+    "FillDial",
+    "Counter",
+    "Scrollbar",
+    "Roller",
+    "Adjuster",
+    "ValueInput",
+    "ValueOutput",
+    "HorSlider",
+    "HorNiceSlider",
+    "HorFillSlider",
+    "HorValueSlider",
+];
+
+const INPUTS: &[&str] = &[
+    "Input",
+    "IntInput",
+    "FloatInput",
+    "SecretInput",
+    "FileInput",
+    "MultilineInput",
+    "Output",
+    "MultilineOutput",
+];
+
+const MENUS: &[&str] = &["MenuBar", "SysMenuBar", "Choice", "InputChoice"];
+
+const DIALS: &[&str] = &["Dial", "LineDial", "FillDial"];
+
+/// The browsers with columns, CheckBrowser only having checkable lines
+const BROWSERS: &[&str] = &[
+    "Browser",
+    "SelectBrowser",
+    "HoldBrowser",
+    "FileBrowser",
+    "MultiBrowser",
+];
+
+const TEXT_WIDGETS: &[&str] = &[
+    "TextDisplay",
+    "TextEditor",
+    "Input",
+    "IntInput",
+    "FloatInput",
+    "SecretInput",
+    "FileInput",
+    "MultilineInput",
+    "Output",
+    "MultilineOutput",
+    "MenuBar",
+    "SysMenuBar",
+    "Choice",
+    "ValueSlider",
+    "ValueInput",
+    "ValueOutput",
+    "HorValueSlider",
+    "CheckBrowser",
+    "Spinner",
+    "Chart",
+    "InputChoice",
+    "HelpView",
+];
+
+/// Every supported widget type
+pub(crate) const WIDGETS: &[&str] = &[
+    "Column",
+    "Row",
+    "Button",
+    "CheckButton",
+    "RadioButton",
+    "ToggleButton",
+    "RadioRoundButton",
+    "ReturnButton",
+    "Frame",
+    "Group",
+    "Pack",
+    "Tile",
+    "Grid",
+    "Tabs",
+    "Scroll",
+    "ColorChooser",
+    "TextDisplay",
+    "TextEditor",
+    "Input",
+    "IntInput",
+    "FloatInput",
+    "SecretInput",
+    "FileInput",
+    "MultilineInput",
+    "Output",
+    "MultilineOutput",
+    "MenuBar",
+    "SysMenuBar",
+    "Choice",
+    "Slider",
+    "NiceSlider",
+    "FillSlider",
+    "ValueSlider",
+    "Dial",
+    "LineDial",
+    "FillDial",
+    "Counter",
+    "Scrollbar",
+    "Roller",
+    "Adjuster",
+    "ValueInput",
+    "ValueOutput",
+    "HorSlider",
+    "HorNiceSlider",
+    "HorFillSlider",
+    "HorValueSlider",
+    "Browser",
+    "SelectBrowser",
+    "HoldBrowser",
+    "FileBrowser",
+    "CheckBrowser",
+    "MultiBrowser",
+    "Table",
+    "TableRow",
+    "Tree",
+    "Spinner",
+    "Chart",
+    "Progress",
+    "InputChoice",
+    "HelpView",
+    "Window",
+    "MenuWindow",
+    "GlutWindow",
+];
+
+/// How problems in the gui description are handled
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Strictness {
+    /// Silently ignore wrong keys or values
+    #[default]
+    Permissive,
+    /// Print every problem to stderr, and build the gui anyway
+    Warn,
+    /// Refuse to build a gui with problems
+    Strict,
+}
+
+/// The kind of problem found in a gui description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// The widget type isn't supported
+    UnknownWidget(String),
+    /// A color which isn't in the #xxxxxx format
+    InvalidColor {
+        /// The property name
+        property: &'static str,
+        /// The offending value
+        value: String,
+    },
+    /// A frame type which doesn't exist
+    InvalidFrame {
+        /// The property name
+        property: &'static str,
+        /// The offending value
+        value: String,
+    },
+    /// A font index which doesn't exist
+    InvalidFont {
+        /// The property name
+        property: &'static str,
+        /// The offending value
+        value: i64,
+    },
+    /// A value which isn't valid for the property, ex. a 0 column width
+    InvalidValue {
+        /// The property name
+        property: &'static str,
+        /// The offending value
+        value: String,
+    },
+    /// A property which has no effect on this widget type
+    InapplicableProperty {
+        /// The property name
+        property: &'static str,
+        /// The widget type
+        widget: String,
+    },
+    /// An id which is already used by another widget
+    DuplicateId(String),
+    /// A callback name which wasn't registered
+    UnknownCallback(String),
+    /// A component parameter which the instance's `props` don't provide
+    MissingParameter {
+        /// The component name
+        component: String,
+        /// The parameter name
+        parameter: String,
+    },
+    /// A component which instantiates itself, directly or through other components
+    RecursiveComponent(String),
+    /// A `$name` reference to a variable which isn't declared
+    UnknownVariable(String),
+    /// A data source name which wasn't registered
+    UnknownSource(String),
+    /// A highlighter name which wasn't registered
+    UnknownHighlighter(String),
+    /// A key which isn't a property, ex. a misspelled one
+    UnknownKey(String),
+}
+
+/// A problem found in a gui description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The path to the widget in the tree, ex. `children[1].children[0]`, empty for the root
+    pub path: String,
+    /// The problem
+    pub kind: DiagnosticKind,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "root: ")?;
+        } else {
+            write!(f, "{}: ", self.path)?;
+        }
+        match &self.kind {
+            DiagnosticKind::UnknownWidget(w) => write!(f, "Unknown widget {w}"),
+            DiagnosticKind::InvalidColor { property, value } => {
+                write!(f, "Invalid color for {property}: {value}")
+            }
+            DiagnosticKind::InvalidFrame { property, value } => {
+                write!(f, "Invalid frame for {property}: {value}")
+            }
+            DiagnosticKind::InvalidFont { property, value } => {
+                write!(f, "Invalid font for {property}: {value}")
+            }
+            DiagnosticKind::InvalidValue { property, value } => {
+                write!(f, "Invalid value for {property}: {value}")
+            }
+            DiagnosticKind::InapplicableProperty { property, widget } => {
+                write!(f, "{property} doesn't apply to {widget}")
+            }
+            DiagnosticKind::DuplicateId(id) => write!(f, "Duplicate id {id}"),
+            DiagnosticKind::UnknownCallback(name) => write!(f, "Unknown callback {name}"),
+            DiagnosticKind::MissingParameter {
+                component,
+                parameter,
+            } => write!(f, "Missing parameter {parameter} of component {component}"),
+            DiagnosticKind::RecursiveComponent(name) => {
+                write!(f, "Component {name} is used recursively")
+            }
+            DiagnosticKind::UnknownVariable(name) => write!(f, "Unknown variable {name}"),
+            DiagnosticKind::UnknownSource(name) => write!(f, "Unknown data source {name}"),
+            DiagnosticKind::UnknownHighlighter(name) => write!(f, "Unknown highlighter {name}"),
+            DiagnosticKind::UnknownKey(key) => write!(f, "Unknown key {key}"),
+        }
+    }
+}
+
+fn is_known(widget: &str) -> bool {
+    WIDGETS.contains(&widget)
+}
+
+pub(crate) fn child_path(path: &str, i: usize) -> String {
+    if path.is_empty() {
+        format!("children[{i}]")
+    } else {
+        format!("{path}.children[{i}]")
+    }
+}
+
+/// Validate a gui description, returning every problem found
+pub fn validate(w: &Widget) -> Vec<Diagnostic> {
+    let mut diags = vec![];
+    let mut ids = HashSet::new();
+    walk(w, None, String::new(), &mut ids, &mut diags);
+    // the other top-level windows are checked like the root
+    for (i, win) in w.windows.iter().flatten().enumerate() {
+        walk(win, None, format!("windows[{i}]"), &mut ids, &mut diags);
+    }
+    diags
+}
+
+fn walk(
+    w: &Widget,
+    parent: Option<&str>,
+    path: String,
+    ids: &mut HashSet<String>,
+    diags: &mut Vec<Diagnostic>,
+) {
+    let ty = w.widget.as_str();
+    // an unresolved include, its content is checked once loaded
+    if w.include.is_some() || ty == "Include" {
+        return;
+    }
+    let mut push = |kind| {
+        diags.push(Diagnostic {
+            path: path.clone(),
+            kind,
+        })
+    };
+    if !is_known(ty) {
+        push(DiagnosticKind::UnknownWidget(ty.to_string()));
+    }
+    for key in &w.unknown {
+        push(DiagnosticKind::UnknownKey(key.clone()));
+    }
+    for name in &w.unresolved {
+        push(DiagnosticKind::UnknownVariable(name.clone()));
+    }
+    for property in &w.dropped {
+        push(DiagnosticKind::InapplicableProperty {
+            property,
+            widget: "Include".to_string(),
+        });
+    }
+    if let Some(id) = &w.id {
+        if !ids.insert(id.clone()) {
+            push(DiagnosticKind::DuplicateId(id.clone()));
+        }
+    }
+    // a window's name is its id
+    if let Some(name) = w.window.as_ref().and_then(|win| win.name.as_ref()) {
+        if !ids.insert(name.clone()) {
+            push(DiagnosticKind::DuplicateId(name.clone()));
+        }
+    }
+    for (property, col) in [
+        ("color", &w.color),
+        ("labelcolor", &w.labelcolor),
+        ("selectioncolor", &w.selectioncolor),
+        ("textcolor", &w.textcolor),
+        (
+            "window.color",
+            &w.window.as_ref().and_then(|win| win.color.clone()),
+        ),
+    ] {
+        if let Some(col) = col {
+            if enums::Color::from_hex_str(col).is_err() {
+                push(DiagnosticKind::InvalidColor {
+                    property,
+                    value: col.clone(),
+                });
+            }
+        }
+    }
+    for (property, f) in [("frame", &w.frame), ("downframe", &w.downframe)] {
+        if let Some(f) = f {
+            if crate::frames::get_frame(f).is_none() {
+                push(DiagnosticKind::InvalidFrame {
+                    property,
+                    value: f.clone(),
+                });
+            }
+        }
+    }
+    if let Some(f) = w.labelfont {
+        if f >= 14 {
+            push(DiagnosticKind::InvalidFont {
+                property: "labelfont",
+                value: f as _,
+            });
+        }
+    }
+    if let Some(f) = w.textfont {
+        if !(0..14).contains(&f) {
+            push(DiagnosticKind::InvalidFont {
+                property: "textfont",
+                value: f as _,
+            });
+        }
+    }
+    // 0 terminates the widths in fltk, hidden columns use -1
+    if let Some(widths) = w.column_widths.as_ref().filter(|v| v.contains(&0)) {
+        push(DiagnosticKind::InvalidValue {
+            property: "column_widths",
+            value: format!("{widths:?}"),
+        });
+    }
+    // a dial's start and end angles
+    if let Some(angles) = w.angles.as_ref().filter(|v| v.len() != 2) {
+        push(DiagnosticKind::InvalidValue {
+            property: "angles",
+            value: format!("{angles:?}"),
+        });
+    }
+    // a chart's lower and upper bounds
+    if let Some(bounds) = w.bounds.as_ref().filter(|v| v.len() != 2) {
+        push(DiagnosticKind::InvalidValue {
+            property: "bounds",
+            value: format!("{bounds:?}"),
+        });
+    }
+    if is_known(ty) {
+        let mut inapplicable = |property, applies: bool, set: bool| {
+            if set && !applies {
+                push(DiagnosticKind::InapplicableProperty {
+                    property,
+                    widget: ty.to_string(),
+                });
+            }
+        };
+        let flex = FLEXES.contains(&ty);
+        let in_flex = parent.map(|p| FLEXES.contains(&p)).unwrap_or(false);
+        // the window section only describes a top-level window
+        inapplicable("window", parent.is_none(), w.window.is_some());
+        inapplicable("windows", path.is_empty(), w.windows.is_some());
+        inapplicable("fixed", in_flex, w.fixed.is_some());
+        inapplicable("weight", in_flex, w.weight.is_some());
+        inapplicable("min", in_flex, w.min.is_some());
+        inapplicable("max", in_flex, w.max.is_some());
+        inapplicable("margin", flex, w.margin.is_some());
+        inapplicable("left", flex, w.left.is_some());
+        inapplicable("top", flex, w.top.is_some());
+        inapplicable("right", flex, w.right.is_some());
+        inapplicable("bottom", flex, w.bottom.is_some());
+        inapplicable("pad", flex, w.pad.is_some());
+        let button = BUTTONS.contains(&ty);
+        inapplicable("downframe", button, w.downframe.is_some());
+        inapplicable("shortcut", button, w.shortcut.is_some());
+        let valuator = VALUATORS.contains(&ty);
+        let range = valuator || ty == "Spinner" || ty == "Progress";
+        inapplicable("minimum", range, w.minimum.is_some());
+        inapplicable("maximum", range, w.maximum.is_some());
+        inapplicable("step", valuator || ty == "Spinner", w.step.is_some());
+        inapplicable(
+            "value",
+            range || button || INPUTS.contains(&ty) || ty == "Choice" || ty == "InputChoice",
+            w.value.is_some(),
+        );
+        inapplicable("precision", valuator, w.precision.is_some());
+        inapplicable("slidersize", SLIDERS.contains(&ty), w.slidersize.is_some());
+        inapplicable("lstep", ty == "Counter", w.lstep.is_some());
+        inapplicable("angles", DIALS.contains(&ty), w.angles.is_some());
+        let browser = BROWSERS.contains(&ty);
+        inapplicable(
+            "items",
+            MENUS.contains(&ty) || browser || ty == "CheckBrowser",
+            w.items.is_some(),
+        );
+        inapplicable("column_widths", browser, w.column_widths.is_some());
+        inapplicable("column_char", browser, w.column_char.is_some());
+        let tree = ty == "Tree";
+        inapplicable("nodes", tree, w.nodes.is_some());
+        inapplicable("showroot", tree, w.showroot.is_some());
+        inapplicable("connectorstyle", tree, w.connectorstyle.is_some());
+        inapplicable(
+            "selectmode",
+            tree || ty == "TableRow",
+            w.selectmode.is_some(),
+        );
+        let table = ty == "Table" || ty == "TableRow";
+        let grid = ty == "Grid";
+        inapplicable("columns", table, w.columns.is_some());
+        inapplicable("rows", table || grid, w.rows.is_some());
+        inapplicable("rowheader", table, w.rowheader.is_some());
+        inapplicable("colheader", table, w.colheader.is_some());
+        inapplicable("rowresize", table, w.rowresize.is_some());
+        inapplicable("colresize", table, w.colresize.is_some());
+        let chart = ty == "Chart";
+        inapplicable("charttype", chart, w.charttype.is_some());
+        inapplicable("bounds", chart, w.bounds.is_some());
+        inapplicable("autosize", chart, w.autosize.is_some());
+        inapplicable("maxsize", chart, w.maxsize.is_some());
+        inapplicable("entries", chart, w.entries.is_some());
+        let display = ty == "TextDisplay" || ty == "TextEditor";
+        inapplicable("text", display, w.text.is_some());
+        inapplicable("textfile", display, w.textfile.is_some());
+        inapplicable("wrap", display, w.wrap.is_some());
+        inapplicable("wrapmargin", display, w.wrapmargin.is_some());
+        inapplicable("linenumbers", display, w.linenumbers.is_some());
+        inapplicable("styles", display, w.styles.is_some());
+        inapplicable("highlight", display, w.highlight.is_some());
+        inapplicable("cols", grid, w.cols.is_some());
+        inapplicable("rowweights", grid, w.rowweights.is_some());
+        inapplicable("colweights", grid, w.colweights.is_some());
+        inapplicable("rowgap", grid, w.rowgap.is_some());
+        inapplicable("colgap", grid, w.colgap.is_some());
+        let in_grid = parent == Some("Grid");
+        inapplicable("row", in_grid, w.row.is_some());
+        inapplicable("col", in_grid, w.col.is_some());
+        inapplicable("rowspan", in_grid, w.rowspan.is_some());
+        inapplicable("colspan", in_grid, w.colspan.is_some());
+        inapplicable("alignment", in_grid, w.alignment.is_some());
+        let text = TEXT_WIDGETS.contains(&ty);
+        inapplicable("textcolor", text, w.textcolor.is_some());
+        inapplicable("textfont", text, w.textfont.is_some());
+        inapplicable("textsize", text, w.textsize.is_some());
+        inapplicable("children", GROUPS.contains(&ty), w.children.is_some());
+    }
+    if let Some(children) = &w.children {
+        for (i, c) in children.iter().enumerate() {
+            walk(c, Some(ty), child_path(&path, i), ids, diags);
+        }
+    }
+}
+
+/// Callback, data source and highlighter names which weren't registered
+fn unregistered(w: &Widget, path: String, registry: &Registry, diags: &mut Vec<Diagnostic>) {
+    let mut unknown_callback = |name: &String| {
+        if !registry.callbacks.contains_key(name) {
+            diags.push(Diagnostic {
+                path: path.clone(),
+                kind: DiagnosticKind::UnknownCallback(name.clone()),
+            });
+        }
+    };
+    if let Some(name) = &w.callback {
+        unknown_callback(name);
+    }
+    let mut items: Vec<_> = w.items.iter().flatten().collect();
+    while let Some(item) = items.pop() {
+        if let Some(name) = &item.callback {
+            unknown_callback(name);
+        }
+        items.extend(item.items.iter().flatten());
+    }
+    if let Some(rows @ Rows::Source(name)) = &w.rows {
+        if rows.count().is_none() && !registry.sources.contains_key(name) {
+            diags.push(Diagnostic {
+                path: path.clone(),
+                kind: DiagnosticKind::UnknownSource(name.clone()),
+            });
+        }
+    }
+    if let Some(name) = &w.highlight {
+        if !registry.highlighters.contains_key(name) {
+            diags.push(Diagnostic {
+                path: path.clone(),
+                kind: DiagnosticKind::UnknownHighlighter(name.clone()),
+            });
+        }
+    }
+    if let Some(children) = &w.children {
+        for (i, c) in children.iter().enumerate() {
+            unregistered(c, child_path(&path, i), registry, diags);
+        }
+    }
+    for (i, win) in w.windows.iter().flatten().enumerate() {
+        unregistered(win, format!("windows[{i}]"), registry, diags);
+    }
+}
+
+/// Validate `w` according to `strictness`, also checking callback, data source and highlighter names against the registry
+pub(crate) fn check(w: &Widget, strictness: Strictness, registry: &Registry) -> Result<(), Error> {
+    // whatever the strictness, an unknown root leaves nothing to show or to reload
+    if !is_known(&w.widget) {
+        return Err(Error::UnknownWidget(w.widget.clone()));
+    }
+    if strictness == Strictness::Permissive {
+        return Ok(());
+    }
+    let mut diags = validate(w);
+    unregistered(w, String::new(), registry, &mut diags);
+    if diags.is_empty() {
+        return Ok(());
+    }
+    match strictness {
+        Strictness::Strict => Err(Error::Invalid(diags)),
+        _ => {
+            for d in diags {
+                eprintln!("{d}");
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::Value;
+    use serde::Deserialize;
+
+    fn widget(ty: &str) -> Widget {
+        Widget::deserialize(Value::Map(vec![(
+            "widget".to_string(),
+            Value::String(ty.to_string()),
+        )]))
+        .unwrap()
+    }
+
+    /// The problems of a `ty` widget with a single property
+    fn problems(ty: &str, property: &str, value: Value) -> Vec<DiagnosticKind> {
+        let w = Widget::deserialize(Value::Map(vec![
+            ("widget".to_string(), Value::String(ty.to_string())),
+            (property.to_string(), value),
+        ]))
+        .unwrap();
+        validate(&w).into_iter().map(|d| d.kind).collect()
+    }
+
+    fn ints(v: &[i64]) -> Value {
+        Value::Seq(v.iter().map(|&i| Value::Int(i)).collect())
+    }
+
+    #[test]
+    fn an_unknown_root_is_always_an_error() {
+        let registry = Registry::default();
+        for strictness in [Strictness::Permissive, Strictness::Warn, Strictness::Strict] {
+            assert!(matches!(
+                check(&widget("Colum"), strictness, &registry),
+                Err(Error::UnknownWidget(w)) if w == "Colum"
+            ));
+            assert!(check(&widget("Column"), strictness, &registry).is_ok());
+        }
+    }
+
+    #[test]
+    fn zero_column_widths_are_reported() {
+        assert_eq!(
+            problems("Browser", "column_widths", ints(&[120, 0, 60])),
+            [DiagnosticKind::InvalidValue {
+                property: "column_widths",
+                value: "[120, 0, 60]".to_string(),
+            }]
+        );
+        assert!(problems("Browser", "column_widths", ints(&[120, -1, 60])).is_empty());
+    }
+
+    #[test]
+    fn angles_are_a_pair() {
+        assert_eq!(
+            problems("Dial", "angles", Value::Int(90)),
+            [DiagnosticKind::InvalidValue {
+                property: "angles",
+                value: "[90]".to_string(),
+            }]
+        );
+        assert!(problems("Dial", "angles", ints(&[0, 270])).is_empty());
+    }
+
+    #[test]
+    fn bounds_are_a_pair() {
+        let floats = |v: &[f64]| Value::Seq(v.iter().map(|&f| Value::Float(f)).collect());
+        assert_eq!(
+            problems("Chart", "bounds", floats(&[0.0, 10.0, 20.0])),
+            [DiagnosticKind::InvalidValue {
+                property: "bounds",
+                value: "[0.0, 10.0, 20.0]".to_string(),
+            }]
+        );
+        assert!(problems("Chart", "bounds", floats(&[0.0, 10.0])).is_empty());
+    }
+}
@@ -0,0 +1,364 @@
+use serde::de::{
+    self, Deserialize, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess,
+    Visitor,
+};
+use std::fmt;
+
+/// A format agnostic description tree, used by formats which aren't read through serde,
+/// and to expand descriptions before deserializing them into widgets.
+/// Deserializing from it is lenient, strings are parsed when a number or bool is expected
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Seq(Vec<Value>),
+    Map(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a key, if this is a map
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Map(map) => map.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Remove a key, if this is a map
+    pub(crate) fn remove(&mut self, key: &str) -> Option<Value> {
+        match self {
+            Value::Map(map) => {
+                let i = map.iter().position(|(k, _)| k == key)?;
+                Some(map.remove(i).1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Set a key, replacing its previous value, if this is a map
+    pub(crate) fn set(&mut self, key: String, value: Value) {
+        if let Value::Map(map) = self {
+            match map.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, v)) => *v = value,
+                None => map.push((key, value)),
+            }
+        }
+    }
+
+    /// The text of a scalar value, used when interpolating it into a string
+    pub(crate) fn to_text(&self) -> Option<String> {
+        match self {
+            Value::Null => Some(String::new()),
+            Value::Bool(v) => Some(v.to_string()),
+            Value::Int(v) => Some(v.to_string()),
+            Value::Float(v) => Some(v.to_string()),
+            Value::String(v) => Some(v.clone()),
+            Value::Seq(_) | Value::Map(_) => None,
+        }
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Int(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Value, E> {
+        Ok(i64::try_from(v).map_or(Value::Float(v as f64), Value::Int))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::Float(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<Value, D::Error> {
+        Value::deserialize(d)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, d: D) -> Result<Value, D::Error> {
+        Value::deserialize(d)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut v = vec![];
+        while let Some(elem) = seq.next_element()? {
+            v.push(elem);
+        }
+        Ok(Value::Seq(v))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut v = vec![];
+        while let Some((key, value)) = map.next_entry::<Value, Value>()? {
+            match key.to_text() {
+                Some(key) => v.push((key, value)),
+                None => return Err(de::Error::custom("expected a string key")),
+            }
+        }
+        Ok(Value::Map(v))
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_any(ValueVisitor)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ValueError(String);
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ValueError {}
+
+impl de::Error for ValueError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ValueError(msg.to_string())
+    }
+}
+
+macro_rules! deserialize_number {
+    ($($method: ident),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match self {
+                    Value::String(s) => {
+                        let s = s.trim();
+                        if let Ok(v) = s.parse::<i64>() {
+                            visitor.visit_i64(v)
+                        } else if let Ok(v) = s.parse::<f64>() {
+                            visitor.visit_f64(v)
+                        } else {
+                            visitor.visit_string(s.to_string())
+                        }
+                    }
+                    v => v.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Value {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::Int(v) => visitor.visit_i64(v),
+            Value::Float(v) => visitor.visit_f64(v),
+            Value::String(v) => visitor.visit_string(v),
+            Value::Seq(v) => visitor.visit_seq(SeqDeserializer(v.into_iter())),
+            Value::Map(v) => visitor.visit_map(MapDeserializer {
+                iter: v.into_iter(),
+                value: None,
+            }),
+        }
+    }
+
+    deserialize_number!(
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_f32,
+        deserialize_f64
+    );
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Value::String(s) => match s.trim() {
+                "true" => visitor.visit_bool(true),
+                "false" => visitor.visit_bool(false),
+                _ => visitor.visit_string(s),
+            },
+            v => v.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Value::Null => visitor.visit_none(),
+            v => visitor.visit_some(v),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            // an empty element, ex. `<label/>` in xml
+            Value::Map(m) if m.is_empty() => visitor.visit_string(String::new()),
+            v => v.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Value::Seq(v) => visitor.visit_seq(SeqDeserializer(v.into_iter())),
+            Value::Map(m) if m.is_empty() => visitor.visit_seq(SeqDeserializer(vec![].into_iter())),
+            // a single element where a sequence is expected
+            v => visitor.visit_seq(SeqDeserializer(vec![v].into_iter())),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 char bytes byte_buf unit unit_struct tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ValueError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+struct SeqDeserializer(std::vec::IntoIter<Value>);
+
+impl<'de> SeqAccess<'de> for SeqDeserializer {
+    type Error = ValueError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        self.0.next().map(|v| seed.deserialize(v)).transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct MapDeserializer {
+    iter: std::vec::IntoIter<(String, Value)>,
+    value: Option<Value>,
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer {
+    type Error = ValueError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.iter.next() {
+            Some((k, v)) => {
+                self.value = Some(v);
+                seed.deserialize(k.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value.take() {
+            Some(v) => seed.deserialize(v),
+            None => Err(de::Error::custom("value is missing")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializing_is_lenient() {
+        let v = Value::Map(vec![
+            ("w".to_string(), Value::String("60".to_string())),
+            ("step".to_string(), Value::String(" 0.5".to_string())),
+            ("hide".to_string(), Value::String("true".to_string())),
+            ("angles".to_string(), Value::Int(90)),
+        ]);
+        #[derive(serde_derive::Deserialize, Debug, PartialEq)]
+        struct Props {
+            w: i32,
+            step: f64,
+            hide: bool,
+            angles: Vec<i32>,
+        }
+        assert_eq!(
+            Props::deserialize(v).unwrap(),
+            Props {
+                w: 60,
+                step: 0.5,
+                hide: true,
+                angles: vec![90],
+            }
+        );
+        assert!(i32::deserialize(Value::String("sixty".to_string())).is_err());
+        assert_eq!(String::deserialize(Value::Map(vec![])).unwrap(), "");
+        assert!(Vec::<i32>::deserialize(Value::Map(vec![]))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,384 @@
+use crate::{value::Value, Error, Format, Widget};
+use xml::reader::{EventReader, XmlEvent};
+
+/// The properties which can be set as attributes, along with their xsd types
+const PROPERTIES: &[(&str, &str)] = &[
+    ("label", "xs:string"),
+    ("id", "xs:string"),
+    ("fixed", "xs:string"),
+    ("weight", "xs:integer"),
+    ("min", "xs:string"),
+    ("max", "xs:string"),
+    ("x", "xs:integer"),
+    ("y", "xs:integer"),
+    ("w", "xs:integer"),
+    ("h", "xs:integer"),
+    ("margin", "xs:integer"),
+    ("left", "xs:integer"),
+    ("top", "xs:integer"),
+    ("right", "xs:integer"),
+    ("bottom", "xs:integer"),
+    ("color", "xs:string"),
+    ("labelcolor", "xs:string"),
+    ("selectioncolor", "xs:string"),
+    ("hide", "xs:boolean"),
+    ("deactivate", "xs:boolean"),
+    ("visible", "xs:boolean"),
+    ("resizable", "xs:boolean"),
+    ("tooltip", "xs:string"),
+    ("image", "xs:string"),
+    ("deimage", "xs:string"),
+    ("labelfont", "xs:integer"),
+    ("labelsize", "xs:integer"),
+    ("align", "xs:integer"),
+    ("when", "xs:integer"),
+    ("frame", "xs:string"),
+    ("downframe", "xs:string"),
+    ("shortcut", "xs:string"),
+    ("pad", "xs:integer"),
+    ("minimum", "xs:double"),
+    ("maximum", "xs:double"),
+    ("slidersize", "xs:double"),
+    ("step", "xs:double"),
+    ("value", "xs:string"),
+    ("precision", "xs:integer"),
+    ("lstep", "xs:double"),
+    ("column_char", "xs:string"),
+    ("showroot", "xs:boolean"),
+    ("connectorstyle", "xs:string"),
+    ("selectmode", "xs:string"),
+    ("rows", "xs:string"),
+    ("rowheader", "xs:boolean"),
+    ("colheader", "xs:boolean"),
+    ("rowresize", "xs:boolean"),
+    ("colresize", "xs:boolean"),
+    ("charttype", "xs:string"),
+    ("autosize", "xs:boolean"),
+    ("maxsize", "xs:integer"),
+    ("text", "xs:string"),
+    ("textfile", "xs:string"),
+    ("wrap", "xs:string"),
+    ("wrapmargin", "xs:integer"),
+    ("linenumbers", "xs:integer"),
+    ("highlight", "xs:string"),
+    ("cols", "xs:integer"),
+    ("row", "xs:integer"),
+    ("col", "xs:integer"),
+    ("rowspan", "xs:integer"),
+    ("colspan", "xs:integer"),
+    ("alignment", "xs:string"),
+    ("rowgap", "xs:integer"),
+    ("colgap", "xs:integer"),
+    ("textcolor", "xs:string"),
+    ("textsize", "xs:integer"),
+    ("textfont", "xs:integer"),
+    ("callback", "xs:string"),
+    ("include", "xs:string"),
+    ("src", "xs:string"),
+];
+
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    /// Elements starting with an uppercase letter are widgets, the rest are properties
+    fn is_widget(&self) -> bool {
+        self.name.starts_with(|c: char| c.is_ascii_uppercase())
+    }
+
+    fn entries(&self) -> Vec<(String, Value)> {
+        let mut entries: Vec<_> = self
+            .attributes
+            .iter()
+            .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+            .collect();
+        for c in self.children.iter().filter(|c| !c.is_widget()) {
+            entries.push((c.name.clone(), c.property()));
+        }
+        entries
+    }
+
+    fn property(&self) -> Value {
+        if self.name == "components" {
+            // <components><LabeledInput><Row>..</Row></LabeledInput></components>
+            Value::Map(
+                self.children
+                    .iter()
+                    .map(|c| {
+                        let body = c.children.iter().find(|b| b.is_widget());
+                        (c.name.clone(), body.map_or(Value::Null, |b| b.widget()))
+                    })
+                    .collect(),
+            )
+        } else if self.name == "windows" {
+            // <windows><Column><window name="settings"/>..</Column></windows>
+            Value::Seq(
+                self.children
+                    .iter()
+                    .filter(|c| c.is_widget())
+                    .map(|c| c.widget())
+                    .collect(),
+            )
+        } else if self.attributes.is_empty() && self.children.is_empty() {
+            match self.text.trim() {
+                // an empty element, ex. `<window/>`
+                "" => Value::Map(vec![]),
+                text => Value::String(text.to_string()),
+            }
+        } else if self.attributes.is_empty() && !self.children.iter().any(|c| c.is_widget()) {
+            // a list, ex. <items><item label="a"/><item label="b"/></items>
+            Value::Seq(self.children.iter().map(|c| c.property()).collect())
+        } else {
+            Value::Map(self.entries())
+        }
+    }
+
+    fn widget(&self) -> Value {
+        let mut map = vec![("widget".to_string(), Value::String(self.name.clone()))];
+        map.extend(self.entries());
+        let text = self.text.trim();
+        if !text.is_empty() && !map.iter().any(|(k, _)| k == "label") {
+            map.push(("label".to_string(), Value::String(text.to_string())));
+        }
+        let children: Vec<_> = self
+            .children
+            .iter()
+            .filter(|c| c.is_widget())
+            .map(|c| c.widget())
+            .collect();
+        if !children.is_empty() {
+            map.push(("children".to_string(), Value::Seq(children)));
+        }
+        Value::Map(map)
+    }
+}
+
+fn read(s: &str) -> Result<Element, Error> {
+    let mut stack: Vec<Element> = vec![];
+    for ev in EventReader::from_str(s) {
+        match ev? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => stack.push(Element {
+                name: name.local_name,
+                attributes: attributes
+                    .into_iter()
+                    .map(|a| (a.name.local_name, a.value))
+                    .collect(),
+                children: vec![],
+                text: String::new(),
+            }),
+            XmlEvent::EndElement { .. } => {
+                let elem = stack.pop().unwrap();
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(elem),
+                    None => return Ok(elem),
+                }
+            }
+            XmlEvent::Characters(t) | XmlEvent::CData(t) => {
+                if let Some(elem) = stack.last_mut() {
+                    elem.text.push_str(&t);
+                }
+            }
+            _ => (),
+        }
+    }
+    Err(Error::parse("xml", "no root element"))
+}
+
+/// Read an xml description, where the element name is the widget type and attributes are its properties.
+/// Descriptions using the older `<root><widget>..</widget><children>..</children></root>` shape
+/// are deserialized as before
+pub(crate) fn parse(s: &str) -> Result<Widget, Error> {
+    let root = read(s)?;
+    if root.name == "root" {
+        return Ok(serde_xml_rs::from_str(s)?);
+    }
+    Format::Xml.deserialize_value(root.widget())
+}
+
+/// Generate an xml schema for descriptions read by the xml feature
+pub fn xml_schema() -> String {
+    let mut s = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" elementFormDefault="qualified">
+  <xs:complexType name="widget" mixed="true">
+    <xs:choice minOccurs="0" maxOccurs="unbounded">
+"#,
+    );
+    for w in crate::validate::WIDGETS.iter().chain(&["Include"]) {
+        s.push_str(&format!("      <xs:element ref=\"{w}\" />\n"));
+    }
+    // lists are written as child elements, ex. <column_widths><w>120</w><w>60</w></column_widths>
+    for name in [
+        "components",
+        "items",
+        "nodes",
+        "columns",
+        "rows",
+        "entries",
+        "styles",
+        "angles",
+        "column_widths",
+        "bounds",
+        "rowweights",
+        "colweights",
+    ] {
+        s.push_str(&format!(
+            r#"      <xs:element name="{name}">
+        <xs:complexType>
+          <xs:sequence>
+            <xs:any processContents="skip" minOccurs="0" maxOccurs="unbounded" />
+          </xs:sequence>
+        </xs:complexType>
+      </xs:element>
+"#
+        ));
+    }
+    // the other top-level windows contain widgets
+    s.push_str(
+        r#"      <xs:element name="windows">
+        <xs:complexType>
+          <xs:choice minOccurs="0" maxOccurs="unbounded">
+"#,
+    );
+    for w in crate::validate::WIDGETS.iter().chain(&["Include"]) {
+        s.push_str(&format!("            <xs:element ref=\"{w}\" />\n"));
+    }
+    s.push_str(
+        r#"          </xs:choice>
+        </xs:complexType>
+      </xs:element>
+"#,
+    );
+    for name in ["props", "vars", "constants", "window"] {
+        s.push_str(&format!(
+            r#"      <xs:element name="{name}">
+        <xs:complexType>
+          <xs:anyAttribute processContents="skip" />
+        </xs:complexType>
+      </xs:element>
+"#
+        ));
+    }
+    s.push_str("    </xs:choice>\n");
+    for (name, ty) in PROPERTIES {
+        s.push_str(&format!(
+            "    <xs:attribute name=\"{name}\" type=\"{ty}\" />\n"
+        ));
+    }
+    s.push_str("  </xs:complexType>\n");
+    for w in crate::validate::WIDGETS.iter().chain(&["Include"]) {
+        s.push_str(&format!("  <xs:element name=\"{w}\" type=\"widget\" />\n"));
+    }
+    s.push_str("</xs:schema>\n");
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_and_text_are_properties() {
+        let w = parse(r#"<Column margin="10"><Button id="inc" fixed="60">Inc</Button></Column>"#)
+            .unwrap();
+        assert_eq!(w.widget, "Column");
+        assert_eq!(w.margin, Some(10));
+        let b = &w.children.as_ref().unwrap()[0];
+        assert_eq!(b.widget, "Button");
+        assert_eq!(b.id.as_deref(), Some("inc"));
+        assert_eq!(b.label.as_deref(), Some("Inc"));
+        assert_eq!(b.fixed, Some(crate::items::Size::Pixels(60)));
+    }
+
+    #[test]
+    fn lists_are_child_elements() {
+        let w = parse(
+            r#"<Column>
+                <Browser><column_widths><w>120</w><w>60</w></column_widths></Browser>
+                <MenuBar>
+                    <items>
+                        <item label="File">
+                            <items><item>Open</item><item label="Quit" shortcut="^q"/></items>
+                        </item>
+                        <item/>
+                    </items>
+                </MenuBar>
+            </Column>"#,
+        )
+        .unwrap();
+        let children = w.children.unwrap();
+        assert_eq!(children[0].column_widths, Some(vec![120, 60]));
+        let items = children[1].items.as_ref().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].label, "File");
+        let nested = items[0].items.as_ref().unwrap();
+        assert_eq!(nested[0].label, "Open");
+        assert_eq!(nested[1].label, "Quit");
+        assert_eq!(nested[1].shortcut.as_deref(), Some("^q"));
+        assert_eq!(items[1].label, "");
+    }
+
+    #[test]
+    fn components_are_expanded() {
+        let w = parse(
+            r#"<Column>
+                <components>
+                    <LabeledInput><Row><Frame label="{{label}}"/><Input/></Row></LabeledInput>
+                </components>
+                <LabeledInput fixed="30"><props label="Name"/></LabeledInput>
+            </Column>"#,
+        )
+        .unwrap();
+        let row = &w.children.unwrap()[0];
+        assert_eq!(row.widget, "Row");
+        assert_eq!(row.fixed, Some(crate::items::Size::Pixels(30)));
+        let frame = &row.children.as_ref().unwrap()[0];
+        assert_eq!(frame.label.as_deref(), Some("Name"));
+    }
+
+    #[test]
+    fn windows_are_widgets() {
+        let w = parse(
+            r#"<Column>
+                <window/>
+                <windows><Row><window name="settings" w="300"/><Button/></Row></windows>
+            </Column>"#,
+        )
+        .unwrap();
+        let main = w.window.unwrap();
+        assert!(main.name.is_none() && main.w.is_none());
+        let windows = w.windows.unwrap();
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].widget, "Row");
+        let settings = windows[0].window.as_ref().unwrap();
+        assert_eq!(settings.name.as_deref(), Some("settings"));
+        assert_eq!(settings.w, Some(300));
+        assert_eq!(windows[0].children.as_ref().unwrap()[0].widget, "Button");
+    }
+
+    #[test]
+    fn the_root_shape_is_still_read() {
+        let w = parse(
+            r#"<root>
+                <widget>Column</widget>
+                <children>
+                    <widget>Button</widget>
+                    <label>Inc</label>
+                    <fixed>60</fixed>
+                </children>
+            </root>"#,
+        )
+        .unwrap();
+        assert_eq!(w.widget, "Column");
+        let b = &w.children.unwrap()[0];
+        assert_eq!(b.widget, "Button");
+        assert_eq!(b.label.as_deref(), Some("Inc"));
+        assert_eq!(b.fixed, Some(crate::items::Size::Pixels(60)));
+    }
+}